@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The answer to one part of a puzzle.
+///
+/// Answers compare equal when the site would be sent the same text, so `Signed(5)`,
+/// `Unsigned(5)` and `Text("5")` are all the same answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    U128(u128),
+    /// An integer of any size as decimal digits, with a leading `-` when negative.
+    BigInt(String),
+    /// Letters, coordinates, comma separated lists and anything else that is not a number.
+    Text(String),
+    /// The part has not been solved yet.
+    Unsolved,
+}
+
+impl Answer {
+    /// Wraps an integer too large for the primitive types, such as one computed with a bigint
+    /// crate.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The integer, displayed as decimal digits.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Answer)` - The answer, `Signed` when the value fits in an `i64`.
+    /// * `None` - If the value is not displayed as a decimal integer.
+    pub fn big(value: impl fmt::Display) -> Option<Answer> {
+        let text = value.to_string();
+        let digits = text.strip_prefix('-').unwrap_or(&text);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some(match text.parse::<i64>() {
+            Ok(value) => Answer::Signed(value),
+            Err(_) => Answer::BigInt(text),
+        })
+    }
+
+    /// Whether the part has been solved.
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Answer::Unsolved)
+    }
+
+    /// The text sent to the site, `None` for an unsolved part.
+    pub fn submission(&self) -> Option<String> {
+        match self {
+            Answer::Unsolved => None,
+            answer => Some(answer.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(value) => write!(f, "{}", value),
+            Answer::Unsigned(value) => write!(f, "{}", value),
+            Answer::U128(value) => write!(f, "{}", value),
+            Answer::BigInt(digits) => f.write_str(digits),
+            Answer::Text(text) => f.write_str(text),
+            Answer::Unsolved => f.write_str("unsolved"),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        self.submission() == other.submission()
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialEq<i64> for Answer {
+    fn eq(&self, other: &i64) -> bool {
+        *self == Answer::Signed(*other)
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self.submission().as_deref() == Some(*other)
+    }
+}
+
+macro_rules! from_integer {
+    ($variant:ident, $wide:ty, $($t:ty),+) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Answer {
+                    Answer::$variant(value as $wide)
+                }
+            }
+        )+
+    };
+}
+
+from_integer!(Signed, i64, i8, i16, i32, i64, isize);
+from_integer!(Unsigned, u64, u8, u16, u32, u64, usize);
+from_integer!(U128, u128, u128);
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Answer {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Signed(value),
+            Err(_) => Answer::BigInt(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl From<char> for Answer {
+    fn from(c: char) -> Answer {
+        Answer::Text(c.to_string())
+    }
+}
+
+impl From<()> for Answer {
+    fn from(_: ()) -> Answer {
+        Answer::Unsolved
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Answer {
+        value.map_or(Answer::Unsolved, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_converted() {
+        assert_eq!(Answer::from(-3i32), Answer::Signed(-3));
+        assert_eq!(Answer::from(7usize), Answer::Unsigned(7));
+        assert!(matches!(Answer::from(u128::MAX), Answer::U128(_)));
+        assert!(matches!(Answer::from(i128::MIN), Answer::BigInt(_)));
+        assert!(matches!(Answer::from(5i128), Answer::Signed(5)));
+    }
+
+    #[test]
+    fn equal_when_submitted_alike() {
+        assert_eq!(Answer::from(5u8), Answer::from(5i64));
+        assert_eq!(Answer::from("5"), 5);
+        assert_eq!(Answer::from(3121910778619i64), 3121910778619);
+        assert_eq!(Answer::from("EZFCHJAB"), "EZFCHJAB");
+        assert_ne!(Answer::from("0"), Answer::Unsolved);
+        assert_eq!(Answer::from(None::<i64>), Answer::Unsolved);
+    }
+
+    #[test]
+    fn big_validated() {
+        let digits = "123456789012345678901234567890";
+        assert_eq!(
+            Answer::big(digits),
+            Some(Answer::BigInt(digits.to_string()))
+        );
+        assert_eq!(Answer::big(-42), Some(Answer::Signed(-42)));
+        assert_eq!(Answer::big("12a"), None);
+        assert_eq!(Answer::big("-"), None);
+    }
+
+    #[test]
+    fn unsolved_not_submitted() {
+        assert_eq!(Answer::Unsolved.submission(), None);
+        assert!(!Answer::Unsolved.is_solved());
+        assert_eq!(Answer::Unsolved.to_string(), "unsolved");
+        assert_eq!(Answer::from(12u64).submission(), Some("12".to_string()));
+    }
+}
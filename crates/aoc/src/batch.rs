@@ -0,0 +1,614 @@
+use crate::bench::{Report, Settings};
+use crate::solution::{Registration, Solved};
+use crate::{AocError, Context, Input, ParseError};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// Starts the line a worker reports the outcome of its day on, after anything the solution
+/// printed. The record separator keeps it from being mistaken for output of a solution.
+const RESULT_MARKER: &str = "\u{1e}aoc-batch-result ";
+
+/// How running one day of a batch ended.
+#[derive(Debug)]
+pub enum Status {
+    Solved(Solved),
+    /// The input could not be read from the cache or fetched.
+    MissingInput(AocError),
+    /// The input was read but the solution could not parse it.
+    ParseFailed {
+        error: ParseError,
+        input: Input,
+    },
+    /// The solution panicked, with the message it panicked with.
+    Panicked(String),
+    /// The worker running the day could not be started or ended without reporting an outcome,
+    /// such as when the solution overflowed its stack.
+    Crashed(String),
+}
+
+/// How a worker reports the outcome of its day to the batch.
+#[derive(Debug, Serialize, Deserialize)]
+enum Outcome {
+    Solved(Solved),
+    ParseFailed(ParseError),
+    Panicked(String),
+}
+
+/// How a worker reports the benchmark of its day.
+#[derive(Debug, Serialize, Deserialize)]
+enum Measurement {
+    Measured(Report),
+    ParseFailed(ParseError),
+    Panicked(String),
+}
+
+/// The outcome of running the solution of one day.
+#[derive(Debug)]
+pub struct DayRun {
+    pub year: i32,
+    pub day: i32,
+    pub status: Status,
+    /// What the solution printed, when it ran in a worker.
+    pub stdout: String,
+    /// What the solution printed to standard error, including the message of a panic.
+    pub stderr: String,
+}
+
+impl DayRun {
+    fn new(solution: &Registration, status: Status) -> DayRun {
+        DayRun {
+            year: solution.year,
+            day: solution.day,
+            status,
+            stdout: String::new(),
+            stderr: String::new(),
+        }
+    }
+
+    /// The answers and timings, when the day was solved.
+    pub fn solved(&self) -> Option<&Solved> {
+        match &self.status {
+            Status::Solved(solved) => Some(solved),
+            _ => None,
+        }
+    }
+}
+
+/// A day waiting to be run, with its input already read.
+#[derive(Debug)]
+pub struct Job {
+    pub solution: Registration,
+    pub input: Result<Input, AocError>,
+}
+
+fn solve(solution: &Registration, input: Input) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| solution.solve(input))) {
+        Ok(Ok(solved)) => Outcome::Solved(solved),
+        Ok(Err(error)) => Outcome::ParseFailed(error),
+        Err(payload) => Outcome::Panicked(panic_message(payload.as_ref())),
+    }
+}
+
+fn status(outcome: Outcome, source: Input) -> Status {
+    match outcome {
+        Outcome::Solved(solved) => Status::Solved(solved),
+        Outcome::ParseFailed(error) => Status::ParseFailed {
+            error,
+            input: source,
+        },
+        Outcome::Panicked(message) => Status::Panicked(message),
+    }
+}
+
+/// Solves a day in this process, turning a parse failure or a panic into the status of the day
+/// instead of ending the batch. Anything the solution prints is not captured.
+///
+/// # Arguments
+///
+/// * `solution` - The solution of the day.
+/// * `input` - The input of the day, or the reason it could not be read.
+///
+/// # Returns
+///
+/// * `DayRun` - How running the day ended.
+pub fn run_day(solution: &Registration, input: Result<Input, AocError>) -> DayRun {
+    let status = match input {
+        Err(e) => Status::MissingInput(e),
+        Ok(input) => {
+            let source = input.clone().normalize(solution.normalization);
+            status(solve(solution, input), source)
+        }
+    };
+    DayRun::new(solution, status)
+}
+
+/// Solves a day on behalf of `run_parallel` and prints the outcome for the batch to read. The
+/// worker process is started by the command given to `run_parallel` and reads the input from
+/// standard input.
+///
+/// # Arguments
+///
+/// * `solution` - The solution of the day.
+/// * `input` - The input of the day.
+pub fn serve(solution: &Registration, input: Input) {
+    report(&solve(solution, input));
+}
+
+/// Benchmarks a day on behalf of `bench_in_worker` and prints the report for it to read.
+///
+/// # Arguments
+///
+/// * `solution` - The solution of the day.
+/// * `input` - The input of the day.
+/// * `settings` - How many times each phase is run.
+pub fn serve_bench(solution: &Registration, input: Input, settings: &Settings) {
+    let bench = panic::catch_unwind(AssertUnwindSafe(|| solution.bench(input, settings)));
+    report(&match bench {
+        Ok(Ok(report)) => Measurement::Measured(report),
+        Ok(Err(error)) => Measurement::ParseFailed(error),
+        Err(payload) => Measurement::Panicked(panic_message(payload.as_ref())),
+    });
+}
+
+fn report(outcome: &impl Serialize) {
+    let report = serde_json::to_string(outcome).expect("outcomes are always serializable");
+    let mut stdout = std::io::stdout().lock();
+    // the solution may have left its last line unfinished
+    let _ = write!(stdout, "\n{}{}\n", RESULT_MARKER, report);
+    let _ = stdout.flush();
+}
+
+/// Reads the input of each solution, in the order given. Inputs are read one after another, so
+/// fetching them keeps to the pace the site asks for.
+///
+/// # Arguments
+///
+/// * `solutions` - The solutions to read the inputs of, such as every solution of a year.
+/// * `context` - Defines the context to read or fetch the inputs with.
+///
+/// # Returns
+///
+/// * `Vec<Job>` - One job per solution, including the days whose input is missing.
+pub async fn read_inputs(solutions: Vec<Registration>, context: &Context) -> Vec<Job> {
+    let mut jobs = vec![];
+    for solution in solutions {
+        let input = crate::get_input(&solution.year, &solution.day, context).await;
+        jobs.push(Job { solution, input });
+    }
+    jobs
+}
+
+/// Runs days on a pool of threads, each day in a worker process of its own so that what a
+/// solution prints is captured apart from the other days, and a crash only ends its own day.
+///
+/// # Arguments
+///
+/// * `jobs` - The days to run.
+/// * `workers` - How many days run at the same time, at least one.
+/// * `command` - Builds the command starting the worker of a day. The worker reads the input from
+///   standard input and reports with `serve`.
+///
+/// # Returns
+///
+/// * `Vec<DayRun>` - The runs, in the order of the jobs.
+pub fn run_parallel<F>(jobs: Vec<Job>, workers: usize, command: F) -> Vec<DayRun>
+where
+    F: Fn(&Registration) -> Command + Sync,
+{
+    let count = jobs.len();
+    let queue = Mutex::new(jobs.into_iter().enumerate().collect::<VecDeque<_>>());
+    let runs = Mutex::new(Vec::with_capacity(count));
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let Some((index, job)) = queue.lock().unwrap().pop_front() else {
+                    break;
+                };
+                let run = match job.input {
+                    Err(e) => DayRun::new(&job.solution, Status::MissingInput(e)),
+                    Ok(input) => run_in_worker(&job.solution, input, command(&job.solution)),
+                };
+                runs.lock().unwrap().push((index, run));
+            });
+        }
+    });
+
+    let mut runs = runs.into_inner().unwrap();
+    runs.sort_by_key(|(index, _)| *index);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+fn run_in_worker(solution: &Registration, input: Input, command: Command) -> DayRun {
+    let source = input.clone().normalize(solution.normalization);
+    let worker = run_worker::<Outcome>(&input, command);
+    let status = match worker.outcome {
+        Ok(outcome) => status(outcome, source),
+        Err(reason) => Status::Crashed(reason),
+    };
+    DayRun {
+        stdout: worker.stdout,
+        stderr: worker.stderr,
+        ..DayRun::new(solution, status)
+    }
+}
+
+/// Benchmarks a day in a worker process of its own, so that what the solution prints on each of
+/// its many runs is captured rather than measured along with it.
+///
+/// # Arguments
+///
+/// * `solution` - The solution of the day.
+/// * `input` - The input of the day.
+/// * `command` - The command starting the worker. The worker reads the input from standard input
+///   and reports with `serve_bench`.
+///
+/// # Returns
+///
+/// * `Ok(Report)` - The statistics of each phase.
+/// * `Err(DayRun)` - How the day failed, along with what the solution printed.
+pub fn bench_in_worker(
+    solution: &Registration,
+    input: Input,
+    command: Command,
+) -> Result<Report, Box<DayRun>> {
+    let source = input.clone().normalize(solution.normalization);
+    let worker = run_worker::<Measurement>(&input, command);
+    let status = match worker.outcome {
+        Ok(Measurement::Measured(report)) => return Ok(report),
+        Ok(Measurement::ParseFailed(error)) => Status::ParseFailed {
+            error,
+            input: source,
+        },
+        Ok(Measurement::Panicked(message)) => Status::Panicked(message),
+        Err(reason) => Status::Crashed(reason),
+    };
+    Err(Box::new(DayRun {
+        stdout: worker.stdout,
+        stderr: worker.stderr,
+        ..DayRun::new(solution, status)
+    }))
+}
+
+/// What a worker printed, and the outcome it reported or the reason it reported none.
+struct Worker<T> {
+    stdout: String,
+    stderr: String,
+    outcome: Result<T, String>,
+}
+
+fn run_worker<T: DeserializeOwned>(input: &Input, mut command: Command) -> Worker<T> {
+    let output = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            let mut stdin = child.stdin.take().expect("stdin is piped");
+            thread::scope(|scope| {
+                // written alongside reading the output, so neither side waits on a full pipe
+                scope.spawn(move || {
+                    // a worker that exits early is reported by its status instead
+                    let _ = stdin.write_all(input.raw().as_bytes());
+                });
+                child.wait_with_output()
+            })
+        });
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            return Worker {
+                stdout: String::new(),
+                stderr: String::new(),
+                outcome: Err(format!("Unable to start the worker, {}", e)),
+            }
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (stdout, outcome) = match stdout.rfind(RESULT_MARKER) {
+        Some(at) => (
+            &stdout[..at],
+            serde_json::from_str::<T>(stdout[at + RESULT_MARKER.len()..].trim_end()).ok(),
+        ),
+        None => (&stdout[..], None),
+    };
+
+    Worker {
+        stdout: stdout.strip_suffix('\n').unwrap_or(stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        outcome: outcome.ok_or_else(|| {
+            format!(
+                "The worker ended with {} without reporting an outcome",
+                output.status
+            )
+        }),
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "The solution panicked".to_string()
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+/// Renders the runs as a table of the answers and timings of each day, with a status column and
+/// the total time of the days that were solved.
+pub fn render_summary(runs: &[DayRun]) -> String {
+    let rows = runs
+        .iter()
+        .map(|run| {
+            let status = match &run.status {
+                Status::Solved(_) => "ok",
+                Status::MissingInput(_) => "missing input",
+                Status::ParseFailed { .. } => "parse failed",
+                Status::Panicked(_) => "panicked",
+                Status::Crashed(_) => "crashed",
+            };
+            let mut row = vec![run.day.to_string(), status.to_string()];
+            if let Some(solved) = run.solved() {
+                let timings = solved.timings;
+                row.extend([
+                    solved.part1.to_string(),
+                    solved.part2.to_string(),
+                    format_duration(timings.parse),
+                    format_duration(timings.part1),
+                    format_duration(timings.part2),
+                    format_duration(timings.total()),
+                ]);
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+    let header = [
+        "Day", "Status", "Part 1", "Part 2", "Parse", "Part 1", "Part 2", "Total",
+    ];
+    let total = runs
+        .iter()
+        .filter_map(DayRun::solved)
+        .map(|solved| solved.timings.total())
+        .sum::<Duration>();
+    let mut footer = vec![String::new(); header.len()];
+    footer[1] = format!("{} of {} solved", runs.len() - failures(runs), runs.len());
+    footer[7] = format_duration(total);
+
+    let mut widths = header.map(str::len);
+    for row in rows.iter().chain([&footer]) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let render_row = |cells: &[String]| {
+        let line = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                // the day and the timings are numbers, so they line up on the right
+                0 | 4.. => format!("{:>width$}", cell),
+                _ => format!("{:<width$}", cell),
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut table = render_row(&header.map(String::from));
+    for row in &rows {
+        table += &render_row(row);
+    }
+    table += &render_row(&footer);
+    table
+}
+
+/// The number of runs that did not solve their day.
+pub fn failures(runs: &[DayRun]) -> usize {
+    runs.iter().filter(|run| run.solved().is_none()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Normalization, Solution};
+
+    struct Lengths;
+
+    impl Solution for Lengths {
+        type Parsed = Vec<usize>;
+        type Part1 = usize;
+        type Part2 = usize;
+
+        const NORMALIZATION: Normalization = Normalization::TrimAll;
+
+        fn parse(input: &Input) -> Result<Vec<usize>, ParseError> {
+            match input.lines().iter().position(|line| line.is_empty()) {
+                Some(row) => Err(ParseError::new(row + 1, 1, "", "Expected a word")),
+                None => Ok(input.lines().iter().map(|line| line.len()).collect()),
+            }
+        }
+
+        fn part1(parsed: &Vec<usize>) -> usize {
+            parsed.iter().sum()
+        }
+
+        fn part2(parsed: &Vec<usize>) -> usize {
+            let longest = parsed.iter().max().unwrap();
+            assert!(*longest < 10, "Word too long");
+            *longest
+        }
+    }
+
+    fn solution() -> Registration {
+        Registration::new::<Lengths>(1002, 4, "Lengths")
+    }
+
+    #[test]
+    fn day_solved() {
+        let run = run_day(&solution(), Ok(Input::from("abc\nde\n")));
+        assert_eq!((run.year, run.day), (1002, 4));
+        let solved = run.solved().unwrap();
+        assert_eq!(solved.part1, 5);
+        assert_eq!(solved.part2, 3);
+    }
+
+    #[test]
+    fn failures_kept_as_status() {
+        let run = run_day(&solution(), Ok(Input::from("abc\n\nde")));
+        match run.status {
+            Status::ParseFailed { error, input } => {
+                assert_eq!(error.line, 2);
+                assert_eq!(input.raw(), "abc\n\nde");
+            }
+            status => panic!("Unexpected status {:?}", status),
+        }
+
+        let run = run_day(&solution(), Ok(Input::from("abcdefghijkl")));
+        assert!(matches!(run.status, Status::Panicked(message) if message == "Word too long"));
+
+        let missing = AocError::Offline {
+            url: "https://adventofcode.com/1002/day/4/input".to_string(),
+        };
+        let run = run_day(&solution(), Err(missing));
+        assert!(matches!(run.status, Status::MissingInput(_)));
+    }
+
+    #[test]
+    fn summary_rendered() {
+        let runs = vec![
+            run_day(&solution(), Ok(Input::from("abc\nde"))),
+            DayRun::new(
+                &Registration::new::<Lengths>(1002, 12, "Lengths"),
+                Status::Panicked("index out of bounds".to_string()),
+            ),
+        ];
+        let summary = render_summary(&runs);
+        let lines = summary.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Day  Status         Part 1  Part 2  "));
+        assert!(lines[1].starts_with("  4  ok             5       3       "));
+        assert_eq!(lines[2], " 12  panicked");
+        assert!(lines[3].starts_with("     1 of 2 solved"));
+        assert_eq!(failures(&runs), 1);
+    }
+
+    // the workers are shell scripts
+    #[cfg(unix)]
+    #[test]
+    fn workers_run_in_parallel() {
+        let solved = Outcome::Solved(Solved {
+            part1: 5.into(),
+            part2: 3.into(),
+            timings: Default::default(),
+        });
+        let report = format!(
+            "{}{}",
+            RESULT_MARKER,
+            serde_json::to_string(&solved).unwrap()
+        );
+        let parse_failed = Outcome::ParseFailed(ParseError::new(2, 1, "", "Expected a word"));
+        let failure = format!(
+            "{}{}",
+            RESULT_MARKER,
+            serde_json::to_string(&parse_failed).unwrap()
+        );
+        let job = |day, input: Result<&str, AocError>| Job {
+            solution: Registration::new::<Lengths>(1002, day, "Lengths"),
+            input: input.map(Input::from),
+        };
+        let jobs = vec![
+            job(1, Ok("abc\nde")),
+            job(2, Ok("abc\n\nde")),
+            job(3, Ok("")),
+            job(
+                4,
+                Err(AocError::Offline {
+                    url: "https://adventofcode.com".to_string(),
+                }),
+            ),
+        ];
+
+        let runs = run_parallel(jobs, 3, |solution| {
+            let script = match solution.day {
+                // the input arrives on standard input and is echoed as the chatter of the day
+                1 => "cat; echo; echo warming up >&2; printf '%s' \"$REPORT\"",
+                2 => "cat > /dev/null; echo; printf '%s' \"$FAILURE\"",
+                _ => "cat > /dev/null; echo partial; exit 3",
+            };
+            let mut command = Command::new("sh");
+            command
+                .args(["-c", script])
+                .env("REPORT", &report)
+                .env("FAILURE", &failure);
+            command
+        });
+
+        assert_eq!(
+            runs.iter().map(|r| r.day).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(runs[0].solved().unwrap().part1, 5);
+        assert_eq!(
+            (runs[0].stdout.as_str(), runs[0].stderr.as_str()),
+            ("abc\nde", "warming up\n")
+        );
+        assert!(
+            matches!(&runs[1].status, Status::ParseFailed { input, .. } if input.raw() == "abc\n\nde")
+        );
+        assert!(matches!(&runs[2].status, Status::Crashed(reason) if reason.contains('3')));
+        assert_eq!(runs[2].stdout, "partial");
+        assert!(matches!(runs[3].status, Status::MissingInput(_)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bench_measured_in_worker() {
+        let settings = Settings {
+            warmup: 0,
+            iterations: 2,
+        };
+        let report = solution().bench(Input::from("abc"), &settings).unwrap();
+        let measured = format!(
+            "{}{}",
+            RESULT_MARKER,
+            serde_json::to_string(&Measurement::Measured(report.clone())).unwrap()
+        );
+        let worker = |script: &str| {
+            let mut command = Command::new("sh");
+            command.args(["-c", script]).env("REPORT", &measured);
+            command
+        };
+
+        let result = bench_in_worker(
+            &solution(),
+            Input::from("abc"),
+            worker("cat > /dev/null; echo chatter; printf '%s' \"$REPORT\""),
+        );
+        assert_eq!(result.unwrap(), report);
+
+        let result = bench_in_worker(
+            &solution(),
+            Input::from("abc"),
+            worker("cat > /dev/null; echo partial; exit 3"),
+        );
+        let run = result.unwrap_err();
+        assert!(matches!(&run.status, Status::Crashed(reason) if reason.contains('3')));
+        assert_eq!(run.stdout, "partial");
+    }
+}
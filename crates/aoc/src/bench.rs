@@ -0,0 +1,400 @@
+use crate::solution::Solution;
+use crate::{AocError, Input, ParseError};
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use std::{fs, io};
+
+/// A change smaller than this fraction of the baseline mean is treated as noise, however
+/// consistent it is.
+const NOISE: f64 = 0.05;
+
+/// The Welch t statistic above which a difference in means is unlikely to be chance, roughly a
+/// 99.7% confidence level for the sample sizes a benchmark uses.
+const T_CRITICAL: f64 = 3.0;
+
+/// How many times each phase is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    /// Runs that are discarded, so caches and the allocator settle before measuring.
+    pub warmup: usize,
+    /// Runs that are measured, at least one.
+    pub iterations: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            warmup: 3,
+            iterations: 20,
+        }
+    }
+}
+
+/// The distribution of the times measured for one phase, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub iterations: usize,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    /// The sample standard deviation, 0 for a single iteration.
+    pub stddev: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Stats {
+    /// Summarizes the times measured for a phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The measured times, at least one.
+    ///
+    /// # Returns
+    ///
+    /// * `Stats` - The summary of the times.
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut nanos = samples
+            .iter()
+            .map(|d| d.as_nanos() as f64)
+            .collect::<Vec<_>>();
+        nanos.sort_by(f64::total_cmp);
+
+        let n = nanos.len();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+        let median = if n % 2 == 0 {
+            (nanos[n / 2 - 1] + nanos[n / 2]) / 2.0
+        } else {
+            nanos[n / 2]
+        };
+        // nearest rank, so a percentile is always one of the measured times
+        let percentile = |p: f64| nanos[((p / 100.0 * n as f64).ceil() as usize).clamp(1, n) - 1];
+
+        Stats {
+            iterations: n,
+            min: nanos[0],
+            median,
+            mean,
+            stddev: variance.sqrt(),
+            p90: percentile(90.0),
+            p95: percentile(95.0),
+            p99: percentile(99.0),
+            max: nanos[n - 1],
+        }
+    }
+}
+
+/// The measurements of every phase of a solution, saved as a baseline to compare later runs
+/// against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub year: i32,
+    pub day: i32,
+    /// The name of the type implementing the solution.
+    pub name: String,
+    pub warmup: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl Report {
+    /// The phases of the report with their labels, in the order they run.
+    pub fn phases(&self) -> [(&'static str, &Stats); 3] {
+        [
+            ("parse", &self.parse),
+            ("part 1", &self.part1),
+            ("part 2", &self.part2),
+        ]
+    }
+
+    /// Reads a baseline saved by `save`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The baseline file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Report)` - The baseline.
+    /// * `Err(AocError)` - If the file cannot be read or is not a baseline.
+    pub fn load(path: &Path) -> Result<Report, AocError> {
+        let io_error = |source| AocError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let text = fs::read_to_string(path).map_err(io_error)?;
+        serde_json::from_str(&text)
+            .map_err(|e| io_error(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    /// Saves the report as a JSON baseline, replacing any earlier one.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The baseline file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the baseline was written.
+    /// * `Err(AocError)` - If the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), AocError> {
+        let io_error = |source| AocError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| io_error(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+        fs::write(path, text + "\n").map_err(io_error)
+    }
+
+    /// Compares every phase against a baseline.
+    pub fn compare(&self, baseline: &Report) -> Vec<Comparison> {
+        self.phases()
+            .into_iter()
+            .zip(baseline.phases())
+            .map(|((phase, current), (_, baseline))| Comparison::new(phase, baseline, current))
+            .collect()
+    }
+}
+
+/// Whether a phase got faster or slower than its baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Improved,
+    Regressed,
+    /// The difference is within the noise of the measurements.
+    Unchanged,
+}
+
+/// How the times of one phase moved against a baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub phase: &'static str,
+    /// The baseline mean in nanoseconds.
+    pub baseline: f64,
+    /// The current mean in nanoseconds.
+    pub current: f64,
+    /// The change of the mean as a fraction of the baseline, positive when slower.
+    pub relative: f64,
+    pub change: Change,
+}
+
+impl Comparison {
+    /// Compares the times of a phase with Welch's t-test. A change is only reported when it is both
+    /// unlikely to be chance and larger than the noise threshold.
+    fn new(phase: &'static str, baseline: &Stats, current: &Stats) -> Comparison {
+        let difference = current.mean - baseline.mean;
+        let error = (baseline.stddev.powi(2) / baseline.iterations as f64
+            + current.stddev.powi(2) / current.iterations as f64)
+            .sqrt();
+        let significant = if error > 0.0 {
+            (difference / error).abs() > T_CRITICAL
+        } else {
+            difference != 0.0
+        };
+        let relative = if baseline.mean > 0.0 {
+            difference / baseline.mean
+        } else {
+            0.0
+        };
+
+        let change = if !significant || relative.abs() < NOISE {
+            Change::Unchanged
+        } else if relative > 0.0 {
+            Change::Regressed
+        } else {
+            Change::Improved
+        };
+
+        Comparison {
+            phase,
+            baseline: baseline.mean,
+            current: current.mean,
+            relative,
+            change,
+        }
+    }
+}
+
+/// Formats a time in nanoseconds the way `Duration` is debug printed, such as `1.25ms`.
+pub fn format_nanos(nanos: f64) -> String {
+    format!("{:.2?}", Duration::from_nanos(nanos.round() as u64))
+}
+
+/// Renders the statistics of each phase as a table.
+pub fn render_report(report: &Report) -> String {
+    let mut table = format!(
+        "{:<8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n",
+        "phase", "min", "median", "mean", "stddev", "p90", "p95", "p99"
+    );
+    for (phase, stats) in report.phases() {
+        table += &format!(
+            "{:<8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n",
+            phase,
+            format_nanos(stats.min),
+            format_nanos(stats.median),
+            format_nanos(stats.mean),
+            format_nanos(stats.stddev),
+            format_nanos(stats.p90),
+            format_nanos(stats.p95),
+            format_nanos(stats.p99)
+        );
+    }
+    table
+}
+
+/// Renders the comparison of each phase against its baseline, one line per phase.
+pub fn render_comparison(comparisons: &[Comparison]) -> String {
+    comparisons
+        .iter()
+        .map(|c| {
+            let verdict = match c.change {
+                Change::Improved => "improved",
+                Change::Regressed => "REGRESSED",
+                Change::Unchanged => "no significant change",
+            };
+            format!(
+                "{:<8} {:>10} -> {:>10} {:>+7.1}%  {}\n",
+                c.phase,
+                format_nanos(c.baseline),
+                format_nanos(c.current),
+                c.relative * 100.0,
+                verdict
+            )
+        })
+        .collect()
+}
+
+/// Measures the parse and each part of a solution. Every warmup and measured run of a part uses
+/// the same parsed input, and every run of the parse starts from a fresh copy of the input.
+/// Anything the solution prints is measured too, `batch::bench_in_worker` captures it instead.
+///
+/// # Arguments
+///
+/// * `input` - The input exactly as it was fetched.
+/// * `settings` - How many times each phase is run.
+///
+/// # Returns
+///
+/// * `Ok([Stats; 3])` - The statistics of the parse, part one and part two.
+/// * `Err(ParseError)` - If the normalized input could not be parsed.
+pub fn run<S: Solution>(input: Input, settings: &Settings) -> Result<[Stats; 3], ParseError> {
+    let iterations = settings.iterations.max(1);
+    let parsed = S::parse(&input.clone().normalize(S::NORMALIZATION))?;
+
+    let mut parse = vec![];
+    let mut part1 = vec![];
+    let mut part2 = vec![];
+    for run in 0..settings.warmup + iterations {
+        let copy = input.clone();
+        let start = Instant::now();
+        black_box(S::parse(&copy.normalize(S::NORMALIZATION))?);
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        black_box(S::part1(black_box(&parsed)));
+        let part1_time = start.elapsed();
+
+        let start = Instant::now();
+        black_box(S::part2(black_box(&parsed)));
+        let part2_time = start.elapsed();
+
+        if run >= settings.warmup {
+            parse.push(parse_time);
+            part1.push(part1_time);
+            part2.push(part2_time);
+        }
+    }
+
+    Ok([
+        Stats::from_samples(&parse),
+        Stats::from_samples(&part1),
+        Stats::from_samples(&part2),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(mean: f64, stddev: f64) -> Stats {
+        Stats {
+            iterations: 20,
+            min: mean,
+            median: mean,
+            mean,
+            stddev,
+            p90: mean,
+            p95: mean,
+            p99: mean,
+            max: mean,
+        }
+    }
+
+    #[test]
+    fn stats_summarized() {
+        let samples = [40, 10, 30, 20, 50, 60, 70, 80, 90, 100]
+            .map(Duration::from_nanos)
+            .to_vec();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!((stats.min, stats.max), (10.0, 100.0));
+        assert_eq!((stats.median, stats.mean), (55.0, 55.0));
+        assert_eq!((stats.p90, stats.p95, stats.p99), (90.0, 100.0, 100.0));
+        assert!((stats.stddev - 30.276).abs() < 0.001);
+
+        let single = Stats::from_samples(&[Duration::from_nanos(7)]);
+        assert_eq!((single.median, single.stddev, single.p99), (7.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn regression_flagged() {
+        let comparison = Comparison::new("part 1", &stats(1000.0, 20.0), &stats(1200.0, 20.0));
+        assert_eq!(comparison.change, Change::Regressed);
+        assert!((comparison.relative - 0.2).abs() < 1e-9);
+
+        let comparison = Comparison::new("part 1", &stats(1000.0, 20.0), &stats(800.0, 20.0));
+        assert_eq!(comparison.change, Change::Improved);
+    }
+
+    #[test]
+    fn noise_ignored() {
+        // large but within the spread of the measurements
+        let comparison = Comparison::new("parse", &stats(1000.0, 900.0), &stats(1300.0, 900.0));
+        assert_eq!(comparison.change, Change::Unchanged);
+        // consistent but too small to matter
+        let comparison = Comparison::new("parse", &stats(1000.0, 0.0), &stats(1020.0, 0.0));
+        assert_eq!(comparison.change, Change::Unchanged);
+    }
+
+    #[test]
+    fn baseline_saved_and_loaded() {
+        let dir = std::env::temp_dir().join("aoc_bench_test");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("baseline.json");
+        let report = Report {
+            year: 2025,
+            day: 5,
+            name: "Day5".to_string(),
+            warmup: 3,
+            parse: stats(10.0, 1.0),
+            part1: stats(20.0, 2.0),
+            part2: stats(30.0, 3.0),
+        };
+
+        report.save(&path).unwrap();
+        assert_eq!(Report::load(&path).unwrap(), report);
+
+        fs::write(&path, "{}").unwrap();
+        assert!(matches!(Report::load(&path), Err(AocError::Io { .. })));
+    }
+}
@@ -0,0 +1,882 @@
+use crate::AocError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable that overrides the default cache root.
+pub const CACHE_DIR_ENV: &str = "AOC_CACHE_DIR";
+
+/// The puzzle input, inside the directory of its day.
+pub const INPUT_FILE: &str = "input.txt";
+/// The puzzle input encrypted with the cache key, in place of `INPUT_FILE`.
+pub const ENCRYPTED_INPUT_FILE: &str = "input.txt.enc";
+/// The description articles of the puzzle page, inside the directory of its day.
+pub const PAGE_FILE: &str = "puzzle.html";
+/// The Markdown rendering of the puzzle description, inside the directory of its day.
+pub const DESCRIPTION_FILE: &str = "puzzle.md";
+/// The answers submitted for the puzzle, inside the directory of its day.
+pub const LEDGER_FILE: &str = "answers.ledger";
+
+/// The directory checked in with the workspace that holds the cache when there is one.
+pub const WORKSPACE_DIR: &str = "Data";
+
+/// The cache root used when none is configured: `AOC_CACHE_DIR` when set, otherwise the `Data`
+/// directory of the workspace when the current directory or one of its parents has one, otherwise
+/// `aoc` in the user cache directory, such as `~/.cache/aoc` on Linux.
+pub fn default_root() -> PathBuf {
+    let start = std::env::current_dir().unwrap_or_default();
+    resolve_root(
+        std::env::var_os(CACHE_DIR_ENV),
+        &start,
+        dirs::cache_dir().map(|dir| dir.join("aoc")),
+    )
+}
+
+/// Resolves the cache root from explicit sources, in the order used by `default_root`.
+///
+/// # Arguments
+///
+/// * `env` - The value of `AOC_CACHE_DIR`, if it is set.
+/// * `start` - The directory to start searching for a `Data` directory from.
+/// * `user_cache` - The cache root in the user cache directory, if there is one.
+///
+/// # Returns
+///
+/// * `PathBuf` - The first root found, `Data` relative to the current directory if there is none.
+pub fn resolve_root(
+    env: Option<std::ffi::OsString>,
+    start: &Path,
+    user_cache: Option<PathBuf>,
+) -> PathBuf {
+    if let Some(dir) = env.filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    start
+        .ancestors()
+        .map(|dir| dir.join(WORKSPACE_DIR))
+        .find(|dir| dir.is_dir())
+        .or(user_cache)
+        .unwrap_or_else(|| PathBuf::from(WORKSPACE_DIR))
+}
+
+/// The directory holding everything cached for a day, relative to the cache root of a profile,
+/// such as `2025/day01`.
+pub fn day_dir(year: &i32, day: &i32) -> PathBuf {
+    PathBuf::from(year.to_string()).join(format!("day{:02}", day))
+}
+
+/// Describes where a cached file came from, stored next to it as `<file>.meta`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// When the file was fetched, in seconds since the Unix epoch.
+    pub fetched_at: u64,
+    /// The address the file was fetched from.
+    pub url: String,
+    /// The named profile the file belongs to, `None` for the default profile.
+    pub profile: Option<String>,
+    /// The SHA-256 of the file contents as lowercase hex, left out for an encrypted input so the
+    /// sidecar does not fingerprint the plaintext.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl Metadata {
+    /// Describes contents fetched just now.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The contents being cached.
+    /// * `url` - The address the contents were fetched from.
+    /// * `profile` - The named profile the contents belong to, `None` for the default profile.
+    pub fn new(contents: &str, url: &str, profile: Option<&str>) -> Metadata {
+        Metadata {
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            url: url.to_string(),
+            profile: profile.map(str::to_string),
+            sha256: Some(hash(contents)),
+        }
+    }
+}
+
+/// The SHA-256 of some contents as lowercase hex.
+pub fn hash(contents: &str) -> String {
+    Sha256::digest(contents.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The path of the metadata sidecar of a cached file.
+pub fn metadata_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".meta");
+    path.with_file_name(name)
+}
+
+/// Reads the metadata sidecar of a cached file.
+///
+/// # Arguments
+///
+/// * `path` - The cached file, not the sidecar.
+///
+/// # Returns
+///
+/// * `Ok(Some(Metadata))` - If the file has a sidecar.
+/// * `Ok(None)` - If the file was cached before sidecars were written.
+/// * `Err(AocError)` - If the sidecar cannot be read or parsed.
+pub fn read_metadata(path: &Path) -> Result<Option<Metadata>, AocError> {
+    let meta_path = metadata_path(path);
+    if !meta_path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(&meta_path).map_err(AocError::cache(&meta_path))?;
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| AocError::cache(&meta_path)(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+/// Reads a cached file, checking it against the hash in its sidecar when there is one.
+///
+/// # Arguments
+///
+/// * `path` - The cached file.
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - The contents of the file.
+/// * `Ok(None)` - If the file has not been cached.
+/// * `Err(AocError)` - If the file cannot be read or no longer matches its recorded hash.
+pub fn read_verified(path: &Path) -> Result<Option<String>, AocError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path).map_err(AocError::cache(path))?;
+    verify(path, &contents)?;
+    Ok(Some(contents))
+}
+
+/// Checks the contents of a cached file against the hash in its sidecar when it records one.
+///
+/// # Arguments
+///
+/// * `path` - The cached file whose sidecar records the hash.
+/// * `contents` - The contents read for the file.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the contents match or there is no hash to check them against.
+/// * `Err(AocError)` - If the sidecar cannot be read or the contents no longer match.
+pub fn verify(path: &Path, contents: &str) -> Result<(), AocError> {
+    if let Some(sha256) = read_metadata(path)?.and_then(|metadata| metadata.sha256) {
+        if hash(contents) != sha256 {
+            return Err(AocError::cache(path)(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the cached contents do not match the hash recorded when they were fetched, delete the file to fetch it again",
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Writes a cached file and its metadata sidecar, each atomically.
+///
+/// # Arguments
+///
+/// * `path` - The cached file.
+/// * `contents` - The contents to cache.
+/// * `metadata` - The description of where the contents came from.
+///
+/// # Returns
+///
+/// * `Ok(())` - If both files were written.
+/// * `Err(AocError)` - If either file cannot be written.
+pub fn write_verified(path: &Path, contents: &str, metadata: &Metadata) -> Result<(), AocError> {
+    write_atomic(path, contents)?;
+    write_metadata(path, metadata)
+}
+
+/// Writes the metadata sidecar of a cached file atomically.
+///
+/// # Arguments
+///
+/// * `path` - The cached file, not the sidecar.
+/// * `metadata` - The description of where the contents came from.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the sidecar was written.
+/// * `Err(AocError)` - If the sidecar cannot be written.
+pub fn write_metadata(path: &Path, metadata: &Metadata) -> Result<(), AocError> {
+    let meta_path = metadata_path(path);
+    let text = serde_json::to_string_pretty(metadata)
+        .map_err(|e| AocError::cache(&meta_path)(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    write_atomic(&meta_path, &text)
+}
+
+/// Writes a file by writing a temporary file next to it and renaming it into place, so a reader
+/// never sees a partially written file. Missing parent directories are created.
+///
+/// # Arguments
+///
+/// * `path` - The file to write.
+/// * `contents` - The contents of the file.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the file was written.
+/// * `Err(AocError)` - If the temporary file cannot be written or renamed.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), AocError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(AocError::cache(parent))?;
+    }
+
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    // unique across processes and across threads of this process
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    name.push(format!(".{}.{}.tmp", std::process::id(), write));
+    let temp = path.with_file_name(name);
+
+    fs::write(&temp, contents).map_err(AocError::cache(&temp))?;
+    fs::rename(&temp, path).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        AocError::cache(path)(e)
+    })
+}
+
+/// The files moved by `migrate`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Migration {
+    /// Each file moved, from its flat name to its place in the nested layout.
+    pub moved: Vec<(PathBuf, PathBuf)>,
+    /// Files left in place because the nested layout already holds a file at their destination.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Moves files cached under the flat `2025.day1.dat` naming into the nested
+/// `2025/day01/input.txt` layout, including the partitions of named profiles.
+///
+/// # Arguments
+///
+/// * `from` - The directory holding the flat files, such as `Data`.
+/// * `to` - The cache root to move them into, which may be the same directory.
+///
+/// # Returns
+///
+/// * `Ok(Migration)` - The files that were moved and the ones left in place.
+/// * `Err(AocError)` - If a directory cannot be read or a file cannot be moved.
+pub fn migrate(from: &Path, to: &Path) -> Result<Migration, AocError> {
+    let mut migration = Migration::default();
+    migrate_dir(from, to, &mut migration)?;
+
+    let profiles = from.join("profiles");
+    if profiles.is_dir() {
+        for entry in fs::read_dir(&profiles).map_err(AocError::cache(&profiles))? {
+            let entry = entry.map_err(AocError::cache(&profiles))?;
+            if entry.path().is_dir() {
+                let target = to.join("profiles").join(entry.file_name());
+                migrate_dir(&entry.path(), &target, &mut migration)?;
+            }
+        }
+    }
+    Ok(migration)
+}
+
+fn migrate_dir(from: &Path, to: &Path, migration: &mut Migration) -> Result<(), AocError> {
+    if !from.is_dir() {
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(from)
+        .map_err(AocError::cache(from))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(AocError::cache(from))?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let source = entry.path();
+        let Some(destination) = entry
+            .file_name()
+            .to_str()
+            .and_then(nested_path)
+            .map(|path| to.join(path))
+        else {
+            continue;
+        };
+        if !source.is_file() {
+            continue;
+        }
+        if destination.exists() {
+            migration.skipped.push(source);
+            continue;
+        }
+
+        move_file(&source, &destination)?;
+        migration.moved.push((source, destination));
+    }
+    Ok(())
+}
+
+/// Maps a flat cache file name to its path in the nested layout.
+fn nested_path(name: &str) -> Option<PathBuf> {
+    let (year, rest) = name.split_once('.')?;
+    let year = year.parse::<i32>().ok()?;
+
+    if let Some(id) = rest
+        .strip_prefix("leaderboard.")
+        .and_then(|r| r.strip_suffix(".json"))
+    {
+        return Some(PathBuf::from(year.to_string()).join(format!("leaderboard.{}.json", id)));
+    }
+
+    let (day, extension) = rest.strip_prefix("day")?.split_once('.')?;
+    let day = day.parse::<i32>().ok()?;
+    let file = match extension {
+        "dat" => INPUT_FILE.to_string(),
+        "dat.meta" => format!("{}.meta", INPUT_FILE),
+        "html" => PAGE_FILE.to_string(),
+        "md" => DESCRIPTION_FILE.to_string(),
+        "ledger" => LEDGER_FILE.to_string(),
+        _ => return None,
+    };
+    Some(day_dir(&year, &day).join(file))
+}
+
+fn move_file(source: &Path, destination: &Path) -> Result<(), AocError> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(AocError::cache(parent))?;
+    }
+    if fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+    // renaming fails across file systems, such as from a checkout into the user cache directory
+    fs::copy(source, destination).map_err(AocError::cache(destination))?;
+    fs::remove_file(source).map_err(AocError::cache(source))
+}
+
+/// An input held in the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The named profile the input belongs to, `None` for the default profile.
+    pub profile: Option<String>,
+    pub year: i32,
+    pub day: i32,
+    /// The cached input file, `input.txt` or its encrypted counterpart.
+    pub path: PathBuf,
+    /// Whether the input is encrypted with the cache key.
+    pub encrypted: bool,
+    /// The size of the input file in bytes.
+    pub size: u64,
+    /// The sidecar of the input, missing for inputs cached before sidecars were written.
+    pub metadata: Option<Metadata>,
+}
+
+impl Entry {
+    /// Checks the entry against optional filters, where `None` matches anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - `Some(None)` for the default profile, `Some(Some(name))` for a named one.
+    /// * `year` - The year of the event.
+    /// * `day` - The day of the event.
+    pub fn matches(
+        &self,
+        profile: Option<Option<&str>>,
+        year: Option<i32>,
+        day: Option<i32>,
+    ) -> bool {
+        profile.is_none_or(|p| p == self.profile.as_deref())
+            && year.is_none_or(|y| y == self.year)
+            && day.is_none_or(|d| d == self.day)
+    }
+}
+
+/// Lists every input in the cache, including the partitions of named profiles.
+///
+/// # Arguments
+///
+/// * `root` - The cache root.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Entry>)` - The inputs ordered by profile, year and day.
+/// * `Err(AocError)` - If a directory or sidecar cannot be read.
+pub fn entries(root: &Path) -> Result<Vec<Entry>, AocError> {
+    let mut entries = profile_entries(root, None)?;
+
+    let profiles = root.join("profiles");
+    for name in sub_dirs(&profiles)? {
+        entries.extend(profile_entries(&profiles.join(&name), Some(name))?);
+    }
+
+    entries.sort_by(|a, b| (&a.profile, a.year, a.day).cmp(&(&b.profile, b.year, b.day)));
+    Ok(entries)
+}
+
+fn profile_entries(dir: &Path, profile: Option<String>) -> Result<Vec<Entry>, AocError> {
+    let mut entries = Vec::new();
+    for year_name in sub_dirs(dir)? {
+        let Ok(year) = year_name.parse::<i32>() else {
+            continue;
+        };
+        for day_name in sub_dirs(&dir.join(&year_name))? {
+            let Some(day) = day_name
+                .strip_prefix("day")
+                .and_then(|d| d.parse::<i32>().ok())
+            else {
+                continue;
+            };
+            let plain = dir.join(day_dir(&year, &day)).join(INPUT_FILE);
+            let encrypted = plain.with_file_name(ENCRYPTED_INPUT_FILE);
+            let (path, file) = match (fs::metadata(&plain), fs::metadata(&encrypted)) {
+                (_, Ok(file)) => (encrypted, file),
+                (Ok(file), _) => (plain.clone(), file),
+                _ => continue,
+            };
+            entries.push(Entry {
+                profile: profile.clone(),
+                year,
+                day,
+                encrypted: path != plain,
+                size: file.len(),
+                // the sidecar sits next to the plaintext name whichever form is cached
+                metadata: read_metadata(&plain)?,
+                path,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn sub_dirs(dir: &Path) -> Result<Vec<String>, AocError> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir).map_err(AocError::cache(dir))? {
+        let entry = entry.map_err(AocError::cache(dir))?;
+        if entry.path().is_dir() {
+            if let Some(name) = entry.file_name().to_str() {
+                names.push(name.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Removes an input from the cache along with its sidecar and the cached puzzle description.
+/// The ledger of submitted answers is kept, it records what the site was told rather than
+/// anything that can be fetched again.
+///
+/// # Arguments
+///
+/// * `entry` - The input to remove.
+///
+/// # Returns
+///
+/// * `Ok(Vec<PathBuf>)` - The files that were removed.
+/// * `Err(AocError)` - If a file cannot be removed.
+pub fn purge(entry: &Entry) -> Result<Vec<PathBuf>, AocError> {
+    let dir = entry.path.parent().unwrap_or(Path::new(""));
+    let mut removed = Vec::new();
+    for file in [
+        INPUT_FILE.to_string(),
+        ENCRYPTED_INPUT_FILE.to_string(),
+        format!("{}.meta", INPUT_FILE),
+        PAGE_FILE.to_string(),
+        DESCRIPTION_FILE.to_string(),
+    ] {
+        let path = dir.join(file);
+        if path.exists() {
+            fs::remove_file(&path).map_err(AocError::cache(&path))?;
+            removed.push(path);
+        }
+    }
+
+    // only succeeds once nothing else, such as a ledger, is left for the day
+    let _ = fs::remove_dir(dir);
+    Ok(removed)
+}
+
+/// Writes the whole cache, including the partitions of named profiles, into a tar archive so
+/// another machine can be seeded without fetching every input again.
+///
+/// # Arguments
+///
+/// * `root` - The cache root.
+/// * `archive` - The archive file to create.
+///
+/// # Returns
+///
+/// * `Ok(Vec<PathBuf>)` - The files added to the archive, relative to the root.
+/// * `Err(AocError)` - If the cache cannot be read or the archive cannot be written.
+pub fn export(root: &Path, archive: &Path) -> Result<Vec<PathBuf>, AocError> {
+    let mut files = Vec::new();
+    collect_files(root, Path::new(""), &mut files)?;
+    files.sort();
+
+    let file = fs::File::create(archive).map_err(AocError::io(archive))?;
+    let mut builder = tar::Builder::new(file);
+    for relative in &files {
+        builder
+            .append_path_with_name(root.join(relative), relative)
+            .map_err(AocError::io(archive))?;
+    }
+    builder.finish().map_err(AocError::io(archive))?;
+    Ok(files)
+}
+
+fn collect_files(root: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<(), AocError> {
+    let dir = root.join(relative);
+    for entry in fs::read_dir(&dir).map_err(AocError::cache(&dir))? {
+        let entry = entry.map_err(AocError::cache(&dir))?;
+        let name = entry.file_name();
+        let path = relative.join(&name);
+        if entry.path().is_dir() {
+            collect_files(root, &path, files)?;
+        } else if !is_transient(&name.to_string_lossy()) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Files that only matter to the machine that wrote them.
+fn is_transient(name: &str) -> bool {
+    name == ".throttle" || name.ends_with(".tmp")
+}
+
+/// The files restored by `import`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Import {
+    /// Each file written into the cache.
+    pub added: Vec<PathBuf>,
+    /// Files in the archive that were left alone because the cache already holds them.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Restores files from an archive written by `export`. Files already in the cache are kept.
+///
+/// # Arguments
+///
+/// * `root` - The cache root.
+/// * `archive` - The archive to read.
+///
+/// # Returns
+///
+/// * `Ok(Import)` - The files that were added and the ones left alone.
+/// * `Err(AocError)` - If the archive cannot be read, holds a path outside the cache, or a file
+///   cannot be written.
+pub fn import(root: &Path, archive: &Path) -> Result<Import, AocError> {
+    let file = fs::File::open(archive).map_err(AocError::io(archive))?;
+    let mut reader = tar::Archive::new(file);
+    let mut import = Import::default();
+
+    for entry in reader.entries().map_err(AocError::io(archive))? {
+        let mut entry = entry.map_err(AocError::io(archive))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().map_err(AocError::io(archive))?.into_owned();
+        let safe = relative
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+        if !safe {
+            return Err(AocError::io(archive)(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is outside the cache", relative.display()),
+            )));
+        }
+
+        let destination = root.join(&relative);
+        if destination.exists() {
+            import.skipped.push(relative);
+            continue;
+        }
+        // encrypted inputs are binary, so nothing is assumed to be text
+        let mut contents = Vec::new();
+        io::Read::read_to_end(&mut entry, &mut contents).map_err(AocError::io(archive))?;
+        write_atomic(&destination, &contents)?;
+        import.added.push(relative);
+    }
+    Ok(import)
+}
+
+/// Recognises bodies that are an error page rather than a puzzle input.
+///
+/// # Arguments
+///
+/// * `body` - The body returned by the site.
+///
+/// # Returns
+///
+/// * `Some(&str)` - The reason the body is not a puzzle input.
+/// * `None` - If the body looks like a puzzle input.
+pub fn reject_input(body: &str) -> Option<&'static str> {
+    let start = body.trim_start().to_lowercase();
+    if body.trim().is_empty() {
+        Some("The input is empty")
+    } else if start.starts_with("<!doctype") || start.starts_with("<html") {
+        Some("The input is an HTML page")
+    } else if body.contains("Puzzle inputs differ by user") {
+        Some("The site asked to log in before serving the input")
+    } else if body.contains("Please don't repeatedly request this endpoint") {
+        Some("The site reported the puzzle has not unlocked")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("aoc_cache_test").join(name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn hash_is_sha256() {
+        assert_eq!(
+            hash("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn round_trip_verified() {
+        let file = get_data_dir("round_trip_verified").join("2023.day1.dat");
+        let metadata = Metadata::new(
+            "1\n2\n",
+            "https://adventofcode.com/2023/day/1/input",
+            Some("alice"),
+        );
+
+        write_verified(&file, "1\n2\n", &metadata).unwrap();
+
+        assert_eq!(read_verified(&file).unwrap(), Some("1\n2\n".to_string()));
+        assert_eq!(read_metadata(&file).unwrap(), Some(metadata));
+        assert!(file.with_file_name("2023.day1.dat.meta").exists());
+    }
+
+    #[test]
+    fn tampered_file_rejected() {
+        let file = get_data_dir("tampered_file_rejected").join("2023.day1.dat");
+        let metadata = Metadata::new("1\n2\n", "url", None);
+        write_verified(&file, "1\n2\n", &metadata).unwrap();
+        fs::write(&file, "<html>login</html>").unwrap();
+
+        assert!(matches!(read_verified(&file), Err(AocError::Cache { .. })));
+    }
+
+    #[test]
+    fn file_without_sidecar_accepted() {
+        let file = get_data_dir("file_without_sidecar_accepted").join("2023.day1.dat");
+        fs::write(&file, "legacy").unwrap();
+
+        assert_eq!(read_verified(&file).unwrap(), Some("legacy".to_string()));
+        assert_eq!(
+            read_verified(&file.with_extension("missing")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn atomic_write_leaves_no_temp_file() {
+        let dir = get_data_dir("atomic_write_leaves_no_temp_file");
+        write_atomic(&dir.join("file"), "first").unwrap();
+        write_atomic(&dir.join("file"), "second").unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("file")).unwrap(), "second");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn day_dir_padded() {
+        assert_eq!(day_dir(&2025, &1), PathBuf::from("2025/day01"));
+        assert_eq!(day_dir(&2015, &25), PathBuf::from("2015/day25"));
+    }
+
+    #[test]
+    fn workspace_root_preferred() {
+        let workspace = get_data_dir("workspace_root_preferred");
+        let nested = workspace.join("crates/day1");
+        fs::create_dir_all(&nested).unwrap();
+        let user_cache = Some(PathBuf::from("/home/user/.cache/aoc"));
+
+        assert_eq!(
+            resolve_root(None, &nested, user_cache.clone()),
+            PathBuf::from("/home/user/.cache/aoc")
+        );
+        fs::create_dir_all(workspace.join("Data")).unwrap();
+        assert_eq!(
+            resolve_root(None, &nested, user_cache.clone()),
+            workspace.join("Data")
+        );
+        assert_eq!(
+            resolve_root(Some("/tmp/aoc".into()), &nested, user_cache),
+            PathBuf::from("/tmp/aoc")
+        );
+    }
+
+    #[test]
+    fn flat_files_migrated() {
+        let from = get_data_dir("flat_files_migrated");
+        for name in [
+            "2025.day1.dat",
+            "2025.day1.dat.meta",
+            "2025.day2.ledger",
+            "2025.day12.md",
+            "2025.day12.html",
+            "2025.leaderboard.101.json",
+            "notes.txt",
+            ".throttle",
+        ] {
+            fs::write(from.join(name), name).unwrap();
+        }
+        fs::create_dir_all(from.join("profiles/alice")).unwrap();
+        fs::write(from.join("profiles/alice/2024.day3.dat"), "alice").unwrap();
+        fs::create_dir_all(from.join("2025/day02")).unwrap();
+        fs::write(from.join("2025/day02/answers.ledger"), "newer").unwrap();
+
+        let result = migrate(&from, &from).unwrap();
+
+        assert_eq!(result.moved.len(), 6);
+        assert_eq!(result.skipped, vec![from.join("2025.day2.ledger")]);
+        let read = |path: &str| fs::read_to_string(from.join(path)).unwrap();
+        assert_eq!(read("2025/day01/input.txt"), "2025.day1.dat");
+        assert_eq!(read("2025/day01/input.txt.meta"), "2025.day1.dat.meta");
+        assert_eq!(read("2025/day12/puzzle.md"), "2025.day12.md");
+        assert_eq!(read("2025/day12/puzzle.html"), "2025.day12.html");
+        assert_eq!(
+            read("2025/leaderboard.101.json"),
+            "2025.leaderboard.101.json"
+        );
+        assert_eq!(read("2025/day02/answers.ledger"), "newer");
+        assert_eq!(read("profiles/alice/2024/day03/input.txt"), "alice");
+        assert!(from.join("notes.txt").exists());
+        assert!(!from.join("2025.day1.dat").exists());
+    }
+
+    fn populate(root: &Path) {
+        let metadata = Metadata::new("1\n2\n", "url", None);
+        write_verified(&root.join("2025/day01/input.txt"), "1\n2\n", &metadata).unwrap();
+        write_atomic(&root.join("2025/day01/puzzle.md"), "# Day 1").unwrap();
+        write_atomic(&root.join("2025/day02/input.txt"), "22").unwrap();
+        write_atomic(&root.join("2025/day02/answers.ledger"), "1\twrong\t5\n").unwrap();
+        write_atomic(&root.join("profiles/alice/2024/day03/input.txt"), "333").unwrap();
+        write_atomic(&root.join(".throttle"), "0").unwrap();
+    }
+
+    #[test]
+    fn entries_listed() {
+        let root = get_data_dir("entries_listed");
+        populate(&root);
+
+        let result = entries(&root).unwrap();
+
+        let keys = result
+            .iter()
+            .map(|e| (e.profile.as_deref(), e.year, e.day, e.size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                (None, 2025, 1, 4),
+                (None, 2025, 2, 2),
+                (Some("alice"), 2024, 3, 3)
+            ]
+        );
+        assert!(result[0].metadata.is_some());
+        assert!(result[1].metadata.is_none());
+        assert!(result[2].matches(Some(Some("alice")), Some(2024), None));
+        assert!(!result[2].matches(Some(None), None, None));
+        assert!(result[0].matches(None, Some(2025), Some(1)));
+    }
+
+    #[test]
+    fn purge_keeps_ledger() {
+        let root = get_data_dir("purge_keeps_ledger");
+        populate(&root);
+
+        for entry in entries(&root).unwrap() {
+            purge(&entry).unwrap();
+        }
+
+        assert!(entries(&root).unwrap().is_empty());
+        assert!(!root.join("2025/day01").exists());
+        assert!(root.join("2025/day02/answers.ledger").exists());
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let root = get_data_dir("export_import_round_trip");
+        populate(&root);
+        let archive = get_data_dir("export_import_round_trip_archive").join("cache.tar");
+
+        let exported = export(&root, &archive).unwrap();
+        assert_eq!(exported.len(), 6);
+        assert!(!exported.contains(&PathBuf::from(".throttle")));
+
+        let target = get_data_dir("export_import_round_trip_target");
+        write_atomic(&target.join("2025/day02/input.txt"), "kept").unwrap();
+        let result = import(&target, &archive).unwrap();
+
+        assert_eq!(result.added.len(), 5);
+        assert_eq!(result.skipped, vec![PathBuf::from("2025/day02/input.txt")]);
+        assert_eq!(
+            read_verified(&target.join("2025/day01/input.txt")).unwrap(),
+            Some("1\n2\n".to_string())
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("2025/day02/input.txt")).unwrap(),
+            "kept"
+        );
+        assert_eq!(entries(&target).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn encrypted_entry_round_trip() {
+        let root = get_data_dir("encrypted_entry_round_trip");
+        let key = crate::CacheKey::generate();
+        let path = root.join("2025/day01/input.txt");
+        crate::crypt::write_encrypted(&path, "1\n2\n", None, &key).unwrap();
+        let archive = get_data_dir("encrypted_entry_round_trip_archive").join("cache.tar");
+        export(&root, &archive).unwrap();
+
+        let target = get_data_dir("encrypted_entry_round_trip_target");
+        let result = import(&target, &archive).unwrap();
+
+        assert_eq!(
+            result.added,
+            vec![PathBuf::from("2025/day01/input.txt.enc")]
+        );
+        assert_eq!(
+            crate::crypt::read_encrypted(&target.join("2025/day01/input.txt"), Some(&key)).unwrap(),
+            Some("1\n2\n".to_string())
+        );
+    }
+
+    #[test]
+    fn error_bodies_rejected() {
+        assert_eq!(reject_input("1 2 3\n4 5 6"), None);
+        assert!(reject_input("").is_some());
+        assert!(reject_input("<!DOCTYPE html>\n<html>").is_some());
+        assert!(reject_input(
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input."
+        )
+        .is_some());
+    }
+}
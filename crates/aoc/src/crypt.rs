@@ -0,0 +1,511 @@
+use crate::cache::{self, Metadata, ENCRYPTED_INPUT_FILE, INPUT_FILE};
+use crate::AocError;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable holding the cache key as hex, which takes precedence over the key file.
+pub const KEY_ENV: &str = "AOC_CACHE_KEY";
+
+/// Marks a file written by `encrypt`, followed by the key fingerprint, the nonce and the ciphertext.
+const MAGIC: &[u8; 4] = b"AOC1";
+const FINGERPRINT_LEN: usize = 8;
+const NONCE_LEN: usize = 12;
+
+/// The key the cached inputs are encrypted with. It is kept outside the repository so the
+/// encrypted inputs can be shared without sharing the puzzles.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CacheKey([u8; 32]);
+
+impl fmt::Debug for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CacheKey({})", self.fingerprint())
+    }
+}
+
+impl CacheKey {
+    /// Generates a new random key.
+    pub fn generate() -> CacheKey {
+        CacheKey(ChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+
+    /// Parses a key written by `to_hex`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The key as 64 hex digits, surrounding whitespace is ignored.
+    /// * `origin` - Where the key was read from, used to report a malformed key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CacheKey)` - The key.
+    /// * `Err(AocError)` - If the text is not 32 bytes of hex.
+    pub fn from_hex(text: &str, origin: &Path) -> Result<CacheKey, AocError> {
+        let text = text.trim();
+        let malformed = || AocError::Encryption {
+            path: origin.to_path_buf(),
+            reason: "the key must be 64 hex digits".to_string(),
+        };
+        if text.len() != 64 || !text.is_ascii() {
+            return Err(malformed());
+        }
+        let mut key = [0u8; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).map_err(|_| malformed())?;
+        }
+        Ok(CacheKey(key))
+    }
+
+    /// The key as 64 lowercase hex digits.
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.0)
+    }
+
+    /// A short identifier of the key, recorded in every file it encrypts so a file encrypted
+    /// with another key is reported as such rather than as corrupt.
+    pub fn fingerprint(&self) -> String {
+        to_hex(&self.fingerprint_bytes())
+    }
+
+    fn fingerprint_bytes(&self) -> [u8; FINGERPRINT_LEN] {
+        let digest = Sha256::digest(self.0);
+        let mut fingerprint = [0u8; FINGERPRINT_LEN];
+        fingerprint.copy_from_slice(&digest[..FINGERPRINT_LEN]);
+        fingerprint
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.0))
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The key file in the user config directory, such as `~/.config/aoc/cache.key` on Linux.
+pub fn key_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("aoc").join("cache.key"))
+}
+
+/// Loads the cache key from `AOC_CACHE_KEY`, falling back to the key file.
+///
+/// # Arguments
+///
+/// * `file` - The key file, usually `key_file()`.
+///
+/// # Returns
+///
+/// * `Ok(Some(CacheKey))` - The configured key.
+/// * `Ok(None)` - If no key is configured, so the cache is kept in plaintext.
+/// * `Err(AocError)` - If the key file cannot be read or the key is malformed.
+pub fn load_key(file: Option<&Path>) -> Result<Option<CacheKey>, AocError> {
+    resolve_key(std::env::var(KEY_ENV).ok(), file)
+}
+
+/// Resolves the cache key from explicit sources, in the order used by `load_key`.
+///
+/// # Arguments
+///
+/// * `env` - The value of `AOC_CACHE_KEY`, if it is set.
+/// * `file` - The key file.
+///
+/// # Returns
+///
+/// * `Ok(Some(CacheKey))` - The first key found.
+/// * `Ok(None)` - If neither source holds a key.
+/// * `Err(AocError)` - If the key file cannot be read or the key is malformed.
+pub fn resolve_key(env: Option<String>, file: Option<&Path>) -> Result<Option<CacheKey>, AocError> {
+    if let Some(value) = env.filter(|v| !v.trim().is_empty()) {
+        return CacheKey::from_hex(&value, Path::new(KEY_ENV)).map(Some);
+    }
+    match file {
+        Some(file) if file.exists() => {
+            let text = fs::read_to_string(file).map_err(AocError::io(file))?;
+            CacheKey::from_hex(&text, file).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Writes a key file readable only by its owner.
+///
+/// # Arguments
+///
+/// * `file` - The key file to write, replaced if it exists.
+/// * `key` - The key to store.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the key was written.
+/// * `Err(AocError)` - If the file cannot be written.
+pub fn save_key(file: &Path, key: &CacheKey) -> Result<(), AocError> {
+    cache::write_atomic(file, format!("{}\n", key.to_hex()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(file, fs::Permissions::from_mode(0o600)).map_err(AocError::io(file))?;
+    }
+    Ok(())
+}
+
+/// Encrypts some contents with a fresh nonce.
+pub fn encrypt(key: &CacheKey, plaintext: &str) -> Vec<u8> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = key
+        .cipher()
+        .encrypt(&nonce, plaintext.as_bytes())
+        .expect("encrypting into memory cannot fail");
+
+    let mut data = Vec::with_capacity(MAGIC.len() + FINGERPRINT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&key.fingerprint_bytes());
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    data
+}
+
+/// Decrypts contents written by `encrypt`.
+///
+/// # Arguments
+///
+/// * `key` - The key the contents were encrypted with.
+/// * `data` - The encrypted contents.
+/// * `path` - The file the contents were read from, used to report failures.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The plaintext.
+/// * `Err(AocError)` - If the contents were encrypted with another key or have been tampered with.
+pub fn decrypt(key: &CacheKey, data: &[u8], path: &Path) -> Result<String, AocError> {
+    let failure = |reason: String| AocError::Encryption {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let header = MAGIC.len() + FINGERPRINT_LEN + NONCE_LEN;
+    if data.len() < header || &data[..MAGIC.len()] != MAGIC {
+        return Err(failure(
+            "the file is not an encrypted cache file".to_string(),
+        ));
+    }
+
+    let fingerprint = &data[MAGIC.len()..MAGIC.len() + FINGERPRINT_LEN];
+    if fingerprint != key.fingerprint_bytes() {
+        return Err(failure(format!(
+            "the file was encrypted with key {} but the configured key is {}",
+            to_hex(fingerprint),
+            key.fingerprint()
+        )));
+    }
+
+    let nonce = Nonce::from_slice(&data[MAGIC.len() + FINGERPRINT_LEN..header]);
+    let plaintext = key
+        .cipher()
+        .decrypt(nonce, &data[header..])
+        .map_err(|_| failure("the file has been modified since it was encrypted".to_string()))?;
+    String::from_utf8(plaintext).map_err(|_| failure("the plaintext is not UTF-8".to_string()))
+}
+
+/// The encrypted counterpart of a cached input, `input.txt.enc` next to `input.txt`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    path.with_file_name(ENCRYPTED_INPUT_FILE)
+}
+
+/// Reads and decrypts a cached input. The cipher detects any change to the file, so unlike a
+/// plaintext input its sidecar records no hash, though one left by an earlier version is checked.
+///
+/// # Arguments
+///
+/// * `path` - The plaintext input, `input.txt`.
+/// * `key` - The configured key, `None` if there is none.
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - The plaintext of the input.
+/// * `Ok(None)` - If there is no encrypted input.
+/// * `Err(AocError)` - If there is no key, the key does not match or the input no longer matches
+///   its recorded hash.
+pub fn read_encrypted(path: &Path, key: Option<&CacheKey>) -> Result<Option<String>, AocError> {
+    let encrypted = encrypted_path(path);
+    if !encrypted.exists() {
+        return Ok(None);
+    }
+    let Some(key) = key else {
+        return Err(AocError::Encryption {
+            path: encrypted,
+            reason: format!(
+                "no cache key is configured, set {} or run `cache keygen`",
+                KEY_ENV
+            ),
+        });
+    };
+
+    let data = fs::read(&encrypted).map_err(AocError::cache(&encrypted))?;
+    let contents = decrypt(key, &data, &encrypted)?;
+    cache::verify(path, &contents)?;
+    Ok(Some(contents))
+}
+
+/// Encrypts an input into the cache along with its sidecar, removing any plaintext copy. The
+/// sidecar keeps no hash of the plaintext, which would identify the input to anyone holding it.
+///
+/// # Arguments
+///
+/// * `path` - The plaintext input, `input.txt`.
+/// * `contents` - The input to cache.
+/// * `metadata` - The description of where the input came from, `None` to keep the sidecar
+///   already there.
+/// * `key` - The key to encrypt with.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the input was written.
+/// * `Err(AocError)` - If a file cannot be written or removed.
+pub fn write_encrypted(
+    path: &Path,
+    contents: &str,
+    metadata: Option<&Metadata>,
+    key: &CacheKey,
+) -> Result<(), AocError> {
+    write_sidecar(path, metadata)?;
+    cache::write_atomic(&encrypted_path(path), encrypt(key, contents))?;
+    if path.exists() {
+        fs::remove_file(path).map_err(AocError::cache(path))?;
+    }
+    Ok(())
+}
+
+/// Writes the sidecar of an encrypted input without the hash of its plaintext, or drops the hash
+/// from the sidecar already there when no metadata is given.
+fn write_sidecar(path: &Path, metadata: Option<&Metadata>) -> Result<(), AocError> {
+    let metadata = match metadata {
+        Some(metadata) => metadata.clone(),
+        None => match cache::read_metadata(path)? {
+            Some(metadata) if metadata.sha256.is_some() => metadata,
+            _ => return Ok(()),
+        },
+    };
+    cache::write_metadata(
+        path,
+        &Metadata {
+            sha256: None,
+            ..metadata
+        },
+    )
+}
+
+/// Encrypts every plaintext input in the cache, including the partitions of named profiles, and
+/// removes the plaintext.
+///
+/// # Arguments
+///
+/// * `root` - The cache root.
+/// * `key` - The key to encrypt with.
+///
+/// # Returns
+///
+/// * `Ok(Vec<PathBuf>)` - The plaintext inputs that were encrypted.
+/// * `Err(AocError)` - If an input cannot be read, no longer matches its hash or cannot be written.
+pub fn encrypt_cache(root: &Path, key: &CacheKey) -> Result<Vec<PathBuf>, AocError> {
+    let mut encrypted = Vec::new();
+    for entry in cache::entries(root)?.iter().filter(|e| !e.encrypted) {
+        let Some(contents) = cache::read_verified(&entry.path)? else {
+            continue;
+        };
+        write_encrypted(&entry.path, &contents, None, key)?;
+        encrypted.push(entry.path.clone());
+    }
+    Ok(encrypted)
+}
+
+/// Re-encrypts every encrypted input in the cache with a new key.
+///
+/// # Arguments
+///
+/// * `root` - The cache root.
+/// * `old` - The key the inputs are encrypted with.
+/// * `new` - The key to encrypt them with instead.
+///
+/// # Returns
+///
+/// * `Ok(Vec<PathBuf>)` - The encrypted inputs that were rewritten.
+/// * `Err(AocError)` - If an input cannot be decrypted with the old key or cannot be written.
+pub fn rotate_cache(root: &Path, old: &CacheKey, new: &CacheKey) -> Result<Vec<PathBuf>, AocError> {
+    let entries = cache::entries(root)?;
+    let encrypted = entries.iter().filter(|e| e.encrypted).collect::<Vec<_>>();
+
+    // decrypt everything first so a wrong old key leaves the cache untouched
+    let mut plaintexts = Vec::new();
+    for entry in &encrypted {
+        let data = fs::read(&entry.path).map_err(AocError::cache(&entry.path))?;
+        plaintexts.push(decrypt(old, &data, &entry.path)?);
+    }
+
+    let mut rotated = Vec::new();
+    for (entry, plaintext) in encrypted.iter().zip(plaintexts) {
+        write_sidecar(&entry.path.with_file_name(INPUT_FILE), None)?;
+        cache::write_atomic(&entry.path, encrypt(new, &plaintext))?;
+        rotated.push(entry.path.clone());
+    }
+    Ok(rotated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join("aoc_crypt_test").join(name);
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        root
+    }
+
+    fn input_path(root: &Path, day: &i32) -> PathBuf {
+        root.join(cache::day_dir(&2025, day)).join(INPUT_FILE)
+    }
+
+    #[test]
+    fn round_trip() {
+        let key = CacheKey::generate();
+        let data = encrypt(&key, "1 2 3\n4 5 6");
+        assert!(!data.windows(5).any(|w| w == b"1 2 3"));
+        assert_eq!(
+            decrypt(&key, &data, Path::new("input.txt.enc")).unwrap(),
+            "1 2 3\n4 5 6"
+        );
+    }
+
+    #[test]
+    fn wrong_key_named() {
+        let key = CacheKey::generate();
+        let other = CacheKey::generate();
+        let data = encrypt(&key, "secret");
+
+        let error = decrypt(&other, &data, Path::new("input.txt.enc")).unwrap_err();
+        assert!(error.to_string().contains(&key.fingerprint()));
+        assert!(error.to_string().contains(&other.fingerprint()));
+    }
+
+    #[test]
+    fn tampering_detected() {
+        let key = CacheKey::generate();
+        let mut data = encrypt(&key, "secret");
+        let last = data.len() - 1;
+        data[last] ^= 1;
+
+        let result = decrypt(&key, &data, Path::new("input.txt.enc"));
+        assert!(matches!(result, Err(AocError::Encryption { .. })));
+        let result = decrypt(&key, b"plaintext", Path::new("input.txt.enc"));
+        assert!(matches!(result, Err(AocError::Encryption { .. })));
+    }
+
+    #[test]
+    fn key_hex_round_trip() {
+        let key = CacheKey::generate();
+        let parsed = CacheKey::from_hex(&format!(" {}\n", key.to_hex()), Path::new("k")).unwrap();
+        assert_eq!(parsed, key);
+        assert!(CacheKey::from_hex("abcd", Path::new("k")).is_err());
+        assert!(CacheKey::from_hex(&"zz".repeat(32), Path::new("k")).is_err());
+        assert!(!format!("{:?}", key).contains(&key.to_hex()));
+    }
+
+    #[test]
+    fn environment_before_file() {
+        let root = build_root("environment_before_file");
+        let file = root.join("cache.key");
+        let from_file = CacheKey::generate();
+        let from_env = CacheKey::generate();
+
+        assert_eq!(resolve_key(None, Some(&file)).unwrap(), None);
+        save_key(&file, &from_file).unwrap();
+        assert_eq!(resolve_key(None, Some(&file)).unwrap(), Some(from_file));
+        assert_eq!(
+            resolve_key(Some(from_env.to_hex()), Some(&file)).unwrap(),
+            Some(from_env)
+        );
+    }
+
+    #[test]
+    fn missing_key_reported() {
+        let root = build_root("missing_key_reported");
+        let path = input_path(&root, &1);
+        let key = CacheKey::generate();
+        write_encrypted(
+            &path,
+            "1 2 3",
+            Some(&Metadata::new("1 2 3", "url", None)),
+            &key,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            read_encrypted(&path, None),
+            Err(AocError::Encryption { .. })
+        ));
+        assert_eq!(
+            read_encrypted(&path, Some(&key)).unwrap(),
+            Some("1 2 3".to_string())
+        );
+    }
+
+    #[test]
+    fn plaintext_migrated() {
+        let root = build_root("plaintext_migrated");
+        let path = input_path(&root, &1);
+        cache::write_verified(&path, "1 2 3", &Metadata::new("1 2 3", "url", None)).unwrap();
+        cache::write_atomic(&input_path(&root, &2), "4 5 6").unwrap();
+
+        let key = CacheKey::generate();
+        let result = encrypt_cache(&root, &key).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert!(!path.exists());
+        assert_eq!(
+            read_encrypted(&path, Some(&key)).unwrap(),
+            Some("1 2 3".to_string())
+        );
+        let metadata = cache::read_metadata(&path).unwrap().unwrap();
+        assert_eq!(metadata.url, "url");
+        assert_eq!(metadata.sha256, None);
+        assert_eq!(
+            read_encrypted(&input_path(&root, &2), Some(&key)).unwrap(),
+            Some("4 5 6".to_string())
+        );
+        assert!(encrypt_cache(&root, &key).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rotation_re_encrypts() {
+        let root = build_root("rotation_re_encrypts");
+        let path = input_path(&root, &1);
+        let old = CacheKey::generate();
+        let new = CacheKey::generate();
+        write_encrypted(
+            &path,
+            "1 2 3",
+            Some(&Metadata::new("1 2 3", "url", None)),
+            &old,
+        )
+        .unwrap();
+
+        // as written by earlier versions, which kept the hash of the plaintext
+        cache::write_metadata(&path, &Metadata::new("1 2 3", "url", None)).unwrap();
+
+        assert!(rotate_cache(&root, &new, &old).is_err());
+        assert_eq!(rotate_cache(&root, &old, &new).unwrap().len(), 1);
+
+        let sidecar = fs::read_to_string(cache::metadata_path(&path)).unwrap();
+        assert!(!sidecar.contains("sha256"));
+
+        assert!(read_encrypted(&path, Some(&old)).is_err());
+        assert_eq!(
+            read_encrypted(&path, Some(&new)).unwrap(),
+            Some("1 2 3".to_string())
+        );
+    }
+}
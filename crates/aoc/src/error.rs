@@ -0,0 +1,200 @@
+use crate::ledger::Refusal;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// The failures that can occur while talking to the Advent of Code website or the local cache.
+#[derive(Debug)]
+pub enum AocError {
+    /// The request could not be sent or the response could not be read.
+    Network { url: String, source: reqwest::Error },
+    /// The site answered with an unexpected HTTP status.
+    Http {
+        url: String,
+        status: u16,
+        body: String,
+    },
+    /// The site did not accept the session, either because it is missing or has expired.
+    Unauthorized { url: String },
+    /// The configured session ID is not in the expected format.
+    InvalidSession { origin: String, reason: String },
+    /// The profile name cannot be used to look up a session or partition the cache.
+    InvalidProfile { name: String },
+    /// The puzzle has not been released yet.
+    NotUnlocked { year: i32, day: i32 },
+    /// The event has no puzzle for the year and day.
+    NoSuchPuzzle { year: i32, day: i32 },
+    /// The input is not cached and the context is offline.
+    NotCached { year: i32, day: i32, path: PathBuf },
+    /// A request was refused because the context is offline.
+    Offline { url: String },
+    /// A file in the data directory could not be read or written.
+    Cache { path: PathBuf, source: io::Error },
+    /// The site answered with a page that did not contain what was expected.
+    MalformedResponse { url: String, reason: String },
+    /// The answer was withheld because the ledger rules it out.
+    Refused { answer: String, refusal: Refusal },
+    /// A file outside the data directory could not be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// An encrypted input could not be decrypted, or the cache key is missing or malformed.
+    Encryption { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::Network { url, .. } => write!(f, "Unable to reach {}", url),
+            AocError::Http { url, status, body } => write!(
+                f,
+                "Failed to fetch {} with status {}.  Response: {}",
+                url, status, body
+            ),
+            AocError::Unauthorized { url } => write!(
+                f,
+                "The session was not accepted by {}, it may be missing or expired",
+                url
+            ),
+            AocError::InvalidSession { origin, reason } => {
+                write!(f, "The session ID from {} is malformed, {}", origin, reason)
+            }
+            AocError::InvalidProfile { name } => write!(
+                f,
+                "The profile name {:?} may only contain letters, digits, '-' and '_'",
+                name
+            ),
+            AocError::NotUnlocked { year, day } => {
+                write!(f, "AOC {} Day {} has not unlocked yet", year, day)
+            }
+            AocError::NoSuchPuzzle { year, day } => {
+                write!(f, "AOC {} has no puzzle for Day {}", year, day)
+            }
+            AocError::NotCached { year, day, path } => write!(
+                f,
+                "AOC {} Day {} is not cached at {} and the network is disabled",
+                year,
+                day,
+                path.display()
+            ),
+            AocError::Offline { url } => {
+                write!(f, "Refused to fetch {} while offline", url)
+            }
+            AocError::Cache { path, .. } => {
+                write!(f, "Unable to access the cache at {}", path.display())
+            }
+            AocError::MalformedResponse { url, reason } => {
+                write!(f, "Unexpected response from {}: {}", url, reason)
+            }
+            AocError::Refused { answer, refusal } => {
+                write!(f, "Answer {} withheld. {}", answer, refusal)
+            }
+            AocError::Io { path, .. } => write!(f, "Unable to access {}", path.display()),
+            AocError::Encryption { path, reason } => {
+                write!(f, "Unable to decrypt {}, {}", path.display(), reason)
+            }
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Network { source, .. } => Some(source),
+            AocError::Cache { source, .. } | AocError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl AocError {
+    pub(crate) fn network(url: &str) -> impl FnOnce(reqwest::Error) -> AocError + '_ {
+        move |source| AocError::Network {
+            url: url.to_string(),
+            source,
+        }
+    }
+
+    pub(crate) fn cache(path: &std::path::Path) -> impl FnOnce(io::Error) -> AocError + '_ {
+        move |source| AocError::Cache {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn io(path: &std::path::Path) -> impl FnOnce(io::Error) -> AocError + '_ {
+        move |source| AocError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+}
+
+/// Reads the body of a response, classifying any failure status.
+///
+/// # Arguments
+///
+/// * `response` - The response returned by the site.
+/// * `year` - The year of the puzzle the request was made for.
+/// * `day` - The day of the puzzle the request was made for, `None` for a request that is not
+///   about one day, such as a leaderboard.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The body of a successful response.
+/// * `Err(AocError)` - The classified failure.
+pub(crate) async fn read_body(
+    response: reqwest::Response,
+    year: &i32,
+    day: Option<&i32>,
+) -> Result<String, AocError> {
+    let url = response.url().to_string();
+    let status = response.status();
+    let body = response.text().await.map_err(AocError::network(&url))?;
+
+    // only a puzzle of a day unlocks, anything else missing is an ordinary failure
+    let locked = status.as_u16() == 404 && body.contains("before it unlocks");
+    if status.is_success() {
+        Ok(body)
+    } else if let (true, Some(day)) = (locked, day) {
+        Err(AocError::NotUnlocked {
+            year: *year,
+            day: *day,
+        })
+    } else if matches!(status.as_u16(), 400 | 401 | 403) || body.contains("log in") {
+        Err(AocError::Unauthorized { url })
+    } else {
+        Err(AocError::Http {
+            url,
+            status: status.as_u16(),
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_chained() {
+        let error = AocError::Cache {
+            path: PathBuf::from("Data/2025.day1.dat"),
+            source: io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+        };
+        assert_eq!(error.source().unwrap().to_string(), "denied");
+        assert_eq!(
+            error.to_string(),
+            "Unable to access the cache at Data/2025.day1.dat"
+        );
+    }
+
+    #[test]
+    fn status_only_has_no_source() {
+        let error = AocError::Http {
+            url: "https://adventofcode.com/2025/day/1/input".to_string(),
+            status: 500,
+            body: "oops".to_string(),
+        };
+        assert!(error.source().is_none());
+    }
+}
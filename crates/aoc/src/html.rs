@@ -0,0 +1,557 @@
+/// Extracts the inner markup of every `<article>` element on a page.
+///
+/// # Arguments
+///
+/// * `page` - A string slice that holds the full HTML page.
+///
+/// # Returns
+///
+/// * `Vec<&str>` - The markup between each opening and closing `article` tag, in page order.
+pub fn articles(page: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut rest = page;
+
+    while let Some(start) = rest.find("<article") {
+        let after_tag = match rest[start..].find('>') {
+            Some(end) => start + end + 1,
+            None => break,
+        };
+        let close = match rest[after_tag..].find("</article>") {
+            Some(close) => after_tag + close,
+            None => break,
+        };
+        result.push(&rest[after_tag..close]);
+        rest = &rest[close + "</article>".len()..];
+    }
+
+    result
+}
+
+/// Reduces a fragment of markup to its visible text.
+///
+/// Tags are removed, entities are decoded and runs of whitespace are collapsed to a single space.
+///
+/// # Arguments
+///
+/// * `fragment` - A string slice that holds the markup to flatten.
+///
+/// # Returns
+///
+/// * `String` - The visible text of the fragment.
+pub fn to_text(fragment: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in fragment.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decodes the character entities the Advent of Code site emits.
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the encoded text.
+///
+/// # Returns
+///
+/// * `String` - The text with named and numeric entities replaced by their characters.
+pub fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16)
+                    .ok()
+                    .and_then(char::from_u32),
+                _ if entity.starts_with('#') => {
+                    entity[1..].parse::<u32>().ok().and_then(char::from_u32)
+                }
+                _ => None,
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// A parsed fragment of markup.
+#[derive(Debug, PartialEq)]
+enum Node {
+    Element {
+        tag: String,
+        href: Option<String>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+const VOID_TAGS: [&str; 6] = ["br", "hr", "img", "input", "link", "meta"];
+
+/// Parses a fragment of markup into a tree, tolerating unclosed and stray closing tags.
+fn parse_nodes(fragment: &str) -> Vec<Node> {
+    // each open element is held as (tag, href, children) until its closing tag is seen
+    let mut stack: Vec<(String, Option<String>, Vec<Node>)> = vec![(String::new(), None, vec![])];
+    let mut rest = fragment;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            stack
+                .last_mut()
+                .unwrap()
+                .2
+                .push(Node::Text(rest.to_string()));
+            break;
+        };
+        if start > 0 {
+            stack
+                .last_mut()
+                .unwrap()
+                .2
+                .push(Node::Text(rest[..start].to_string()));
+        }
+        let Some(end) = rest[start..].find('>').map(|end| start + end) else {
+            stack
+                .last_mut()
+                .unwrap()
+                .2
+                .push(Node::Text(rest[start..].to_string()));
+            break;
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim().to_ascii_lowercase();
+            if stack.iter().skip(1).any(|(open, _, _)| *open == name) {
+                loop {
+                    let (open, href, children) = stack.pop().unwrap();
+                    let done = open == name;
+                    stack.last_mut().unwrap().2.push(Node::Element {
+                        tag: open,
+                        href,
+                        children,
+                    });
+                    if done {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let href = attribute(tag, "href");
+        if VOID_TAGS.contains(&name.as_str()) || tag.ends_with('/') {
+            stack.last_mut().unwrap().2.push(Node::Element {
+                tag: name,
+                href,
+                children: vec![],
+            });
+        } else {
+            stack.push((name, href, vec![]));
+        }
+    }
+
+    while stack.len() > 1 {
+        let (tag, href, children) = stack.pop().unwrap();
+        stack.last_mut().unwrap().2.push(Node::Element {
+            tag,
+            href,
+            children,
+        });
+    }
+    stack.pop().unwrap().2
+}
+
+/// Reads a double quoted attribute from the inside of a tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let marker = format!("{}=\"", name);
+    let start = tag.find(&marker)? + marker.len();
+    let end = start + tag[start..].find('"')?;
+    Some(decode_entities(&tag[start..end]))
+}
+
+/// Converts a fragment of puzzle markup to Markdown.
+///
+/// Covers the elements used in puzzle descriptions: headings, paragraphs, lists, preformatted
+/// examples, emphasis, inline code and links.
+///
+/// # Arguments
+///
+/// * `fragment` - A string slice that holds the markup to convert.
+///
+/// # Returns
+///
+/// * `String` - The Markdown rendering of the fragment.
+pub fn to_markdown(fragment: &str) -> String {
+    let mut markdown = String::new();
+    render_blocks(&parse_nodes(fragment), 0, &mut markdown);
+    markdown.trim_end().to_string() + "\n"
+}
+
+fn render_blocks(nodes: &[Node], depth: usize, markdown: &mut String) {
+    let mut inline = vec![];
+
+    for node in nodes {
+        let block = match node {
+            Node::Element { tag, .. } => matches!(
+                tag.as_str(),
+                "h1" | "h2" | "h3" | "h4" | "p" | "pre" | "ul" | "ol" | "div" | "form"
+            ),
+            Node::Text(_) => false,
+        };
+        if !block {
+            inline.push(node);
+            continue;
+        }
+
+        flush_inline(&inline, markdown);
+        inline.clear();
+
+        let Node::Element { tag, children, .. } = node else {
+            continue;
+        };
+        match tag.as_str() {
+            "h1" | "h2" | "h3" | "h4" => {
+                let level = tag[1..].parse::<usize>().unwrap_or(2);
+                markdown.push_str(&"#".repeat(level));
+                markdown.push(' ');
+                markdown.push_str(render_inline(children).trim());
+                markdown.push_str("\n\n");
+            }
+            "p" => {
+                markdown.push_str(render_inline(children).trim());
+                markdown.push_str("\n\n");
+            }
+            "pre" => {
+                let text = decode_entities(&raw_text(children));
+                markdown.push_str("```\n");
+                markdown.push_str(text.trim_end_matches('\n'));
+                markdown.push_str("\n```\n\n");
+            }
+            "ul" | "ol" => {
+                let ordered = tag == "ol";
+                let items = children.iter().filter(|c| node_tag(c) == "li");
+                for (index, item) in items.enumerate() {
+                    let Node::Element { children, .. } = item else {
+                        continue;
+                    };
+                    let bullet = match ordered {
+                        true => format!("{}.", index + 1),
+                        false => "-".to_string(),
+                    };
+                    let (text, nested): (Vec<&Node>, Vec<&Node>) = children
+                        .iter()
+                        .partition(|c| !matches!(node_tag(c), "ul" | "ol"));
+                    markdown.push_str(&"  ".repeat(depth));
+                    markdown.push_str(&bullet);
+                    markdown.push(' ');
+                    markdown.push_str(render_inline_refs(&text).trim());
+                    markdown.push('\n');
+                    for list in nested {
+                        let mut sub = String::new();
+                        render_blocks(std::slice::from_ref(list), depth + 1, &mut sub);
+                        markdown.push_str(sub.trim_end_matches('\n'));
+                        markdown.push('\n');
+                    }
+                }
+                if depth == 0 {
+                    markdown.push('\n');
+                }
+            }
+            _ => render_blocks(children, depth, markdown),
+        }
+    }
+
+    flush_inline(&inline, markdown);
+}
+
+fn flush_inline(inline: &[&Node], markdown: &mut String) {
+    let text = render_inline_refs(inline);
+    if !text.trim().is_empty() {
+        markdown.push_str(text.trim());
+        markdown.push_str("\n\n");
+    }
+}
+
+fn node_tag(node: &Node) -> &str {
+    match node {
+        Node::Element { tag, .. } => tag,
+        Node::Text(_) => "",
+    }
+}
+
+fn render_inline(nodes: &[Node]) -> String {
+    render_inline_refs(&nodes.iter().collect::<Vec<_>>())
+}
+
+fn render_inline_refs(nodes: &[&Node]) -> String {
+    let mut text = String::new();
+
+    for node in nodes {
+        match node {
+            Node::Text(t) => {
+                let decoded = decode_entities(t);
+                let mut collapsed = decoded.split_whitespace().collect::<Vec<_>>().join(" ");
+                if decoded.starts_with(char::is_whitespace) && !collapsed.is_empty() {
+                    collapsed.insert(0, ' ');
+                }
+                if decoded.ends_with(char::is_whitespace) {
+                    collapsed.push(' ');
+                }
+                if text.ends_with(' ') && collapsed.starts_with(' ') {
+                    collapsed.remove(0);
+                }
+                text.push_str(&collapsed);
+            }
+            Node::Element {
+                tag,
+                href,
+                children,
+            } => match tag.as_str() {
+                "em" | "i" => {
+                    if let [Node::Element { tag, children, .. }] = children.as_slice() {
+                        if tag == "code" {
+                            text.push_str(&format!("*{}*", code_span(children)));
+                            continue;
+                        }
+                    }
+                    text.push_str(&format!("*{}*", render_inline(children).trim()));
+                }
+                "b" | "strong" => {
+                    text.push_str(&format!("**{}**", render_inline(children).trim()));
+                }
+                "code" => {
+                    if let [Node::Element { tag, children, .. }] = children.as_slice() {
+                        if tag == "em" {
+                            text.push_str(&format!("*{}*", code_span(children)));
+                            continue;
+                        }
+                    }
+                    text.push_str(&code_span(children));
+                }
+                "a" => {
+                    let label = render_inline(children);
+                    match href {
+                        Some(href) => text.push_str(&format!("[{}]({})", label.trim(), href)),
+                        None => text.push_str(&label),
+                    }
+                }
+                "br" => text.push_str("  \n"),
+                _ => text.push_str(&render_inline(children)),
+            },
+        }
+    }
+
+    text
+}
+
+fn code_span(children: &[Node]) -> String {
+    let code = decode_entities(&raw_text(children));
+    let fence = if code.contains('`') { "``" } else { "`" };
+    format!("{}{}{}", fence, code, fence)
+}
+
+/// A preformatted block together with the text of the paragraph that introduces it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preformatted {
+    pub lead: String,
+    pub text: String,
+}
+
+/// Collects every `<pre>` block in a fragment of markup.
+///
+/// # Arguments
+///
+/// * `fragment` - A string slice that holds the markup to search.
+///
+/// # Returns
+///
+/// * `Vec<Preformatted>` - The decoded, whitespace-exact text of each block in document order.
+pub fn preformatted(fragment: &str) -> Vec<Preformatted> {
+    fn walk(nodes: &[Node], lead: &mut String, found: &mut Vec<Preformatted>) {
+        for node in nodes {
+            let Node::Element { tag, children, .. } = node else {
+                continue;
+            };
+            match tag.as_str() {
+                "pre" => found.push(Preformatted {
+                    lead: lead.clone(),
+                    text: decode_entities(&raw_text(children)),
+                }),
+                "p" => *lead = render_inline(children).trim().to_string(),
+                _ => walk(children, lead, found),
+            }
+        }
+    }
+
+    let mut found = vec![];
+    walk(&parse_nodes(fragment), &mut String::new(), &mut found);
+    found
+}
+
+/// Collects the text of every emphasised code span, written as `<code><em>` or `<em><code>`.
+///
+/// # Arguments
+///
+/// * `fragment` - A string slice that holds the markup to search.
+///
+/// # Returns
+///
+/// * `Vec<String>` - The decoded text of each span in document order.
+pub fn emphasised_code(fragment: &str) -> Vec<String> {
+    fn walk(nodes: &[Node], found: &mut Vec<String>) {
+        for node in nodes {
+            let Node::Element { tag, children, .. } = node else {
+                continue;
+            };
+            let nested = match children.as_slice() {
+                [Node::Element { tag: inner, .. }] => inner.as_str(),
+                _ => "",
+            };
+            match (tag.as_str(), nested) {
+                ("code", "em") | ("em", "code") => {
+                    found.push(decode_entities(&raw_text(children)).trim().to_string())
+                }
+                ("pre", _) => {}
+                _ => walk(children, found),
+            }
+        }
+    }
+
+    let mut found = vec![];
+    walk(&parse_nodes(fragment), &mut found);
+    found
+}
+
+/// Concatenates the text beneath a set of nodes without touching whitespace.
+fn raw_text(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(t) => t.clone(),
+            Node::Element { children, .. } => raw_text(children),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn articles_extracted_in_order() {
+        let page = r#"<main><article class="day-desc"><h2>one</h2></article><p>between</p><article><p>two</p></article></main>"#;
+        let result = articles(page);
+        assert_eq!(result, vec!["<h2>one</h2>", "<p>two</p>"]);
+    }
+
+    #[test]
+    fn unterminated_article_ignored() {
+        let result = articles("<article><p>never closed</p>");
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn text_strips_tags_and_whitespace() {
+        let result = to_text("<p>That's the\n  <em>right</em> answer!</p>");
+        assert_eq!(result, "That's the right answer!");
+    }
+
+    #[test]
+    fn entities_decoded() {
+        let result = decode_entities("a &lt;b&gt; &amp; &#39;c&#x27; &unknown; &");
+        assert_eq!(result, "a <b> & 'c' &unknown; &");
+    }
+
+    #[test]
+    fn markdown_keeps_preformatted_whitespace() {
+        let result = to_markdown("<pre><code>123 328\n 45 64\n  *   +  \n&lt;x&gt;</code></pre>");
+        assert_eq!(result, "```\n123 328\n 45 64\n  *   +  \n<x>\n```\n");
+    }
+
+    #[test]
+    fn markdown_nests_lists() {
+        let result =
+            to_markdown("<ol><li>first<ul><li><em>inner</em></li></ul></li>\n<li>second</li></ol>");
+        assert_eq!(result, "1. first\n  - *inner*\n2. second\n");
+    }
+
+    #[test]
+    fn markdown_emphasised_code() {
+        let result = to_markdown("<p>is <code><em>3</em></code> or <em><code>4</code></em></p>");
+        assert_eq!(result, "is *`3`* or *`4`*\n");
+    }
+
+    #[test]
+    fn preformatted_blocks_with_lead() {
+        let result = preformatted(
+            "<p>For <em>example</em>:</p>\n<pre><code>a &amp; b\n  c\n</code></pre><p>Then:</p><pre>d</pre>",
+        );
+        assert_eq!(
+            result,
+            vec![
+                Preformatted {
+                    lead: "For *example*:".to_string(),
+                    text: "a & b\n  c\n".to_string()
+                },
+                Preformatted {
+                    lead: "Then:".to_string(),
+                    text: "d".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn emphasised_code_spans() {
+        let result = emphasised_code(
+            "<p><code>1</code> <code><em>2</em></code> <em>3</em> <em><code>4</code></em></p><pre><code><em>5</em></code></pre>",
+        );
+        assert_eq!(result, vec!["2", "4"]);
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{AocError, Context};
+use reqwest::{Client, Response};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Identifies the tool to the site, as requested by the Advent of Code maintainer.
+pub const DEFAULT_USER_AGENT: &str =
+    "github.com/Tedford/advent-of-code-2025 (aoc runner; +https://github.com/Tedford/advent-of-code-2025)";
+
+/// The file in the data directory recording when the last request was sent.
+const THROTTLE_FILE: &str = ".throttle";
+
+/// Settings for how requests are made to the site.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Sent with every request so the site can tell who is making them.
+    pub user_agent: String,
+    /// How long a single request may take before it is abandoned.
+    pub timeout: Duration,
+    /// How many times a failed `GET` is retried after a server error or timeout.
+    pub retries: u32,
+    /// The delay before the first retry, doubled for each further retry.
+    pub backoff: Duration,
+    /// The minimum time between any two requests, shared by every process using the data directory.
+    pub min_interval: Duration,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            backoff: Duration::from_secs(1),
+            min_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Adds a way to contact the person running the tool to the user agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact` - An email address or URL the maintainer can use to get in touch.
+    ///
+    /// # Returns
+    ///
+    /// * `HttpConfig` - The configuration with the contact appended to the user agent.
+    pub fn with_contact(mut self, contact: &str) -> Self {
+        self.user_agent = format!("{} contact: {}", DEFAULT_USER_AGENT, contact);
+        self
+    }
+}
+
+/// Builds a client that presents the session cookie for the site in the context.
+///
+/// # Arguments
+///
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(Client)` - A client carrying the session cookie and user agent.
+/// * `Err(AocError)` - If the client could not be constructed.
+pub(crate) fn build_client(context: &Context) -> Result<Client, AocError> {
+    let jar = reqwest::cookie::Jar::default();
+
+    // the value of domain needs to be extracted from context.url with the schema removed
+    let domain = context
+        .url
+        .host_str()
+        .ok_or_else(|| AocError::MalformedResponse {
+            url: context.url.to_string(),
+            reason: "The site URL has no host".to_string(),
+        })?;
+    jar.add_cookie_str(
+        format!("session={}; Domain={}; Path=/", context.session_id, domain).as_str(),
+        &context.url,
+    );
+
+    Client::builder()
+        .cookie_provider(Arc::new(jar))
+        .user_agent(&context.http.user_agent)
+        .timeout(context.http.timeout)
+        .build()
+        .map_err(AocError::network(context.url.as_str()))
+}
+
+/// Sends a `GET` request, retrying server errors and timeouts with exponential backoff.
+///
+/// # Arguments
+///
+/// * `url` - The address to fetch.
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(Response)` - The first response that is not a server error, or the last one if every
+///   attempt failed.
+/// * `Err(AocError)` - If the request could not be sent on the final attempt.
+pub(crate) async fn get(url: &str, context: &Context) -> Result<Response, AocError> {
+    refuse_offline(url, context)?;
+    let client = context.client()?;
+    let mut delay = context.http.backoff;
+    let mut attempt = 0;
+    loop {
+        throttle(context).await?;
+        let result = client.get(url).send().await;
+
+        let retryable = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => e.is_timeout() || e.is_connect(),
+        };
+        if !retryable || attempt >= context.http.retries {
+            return result.map_err(AocError::network(url));
+        }
+
+        attempt += 1;
+        println!(
+            "Request to {} failed, retrying in {:?} ({} of {})",
+            url, delay, attempt, context.http.retries
+        );
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+}
+
+/// Sends a form as a `POST` request. Submissions are never retried, a repeated answer would
+/// count against the submission rate limit.
+///
+/// # Arguments
+///
+/// * `url` - The address to post to.
+/// * `form` - The fields of the form.
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(Response)` - The response of the site.
+/// * `Err(AocError)` - If the request could not be sent.
+pub(crate) async fn post_form(
+    url: &str,
+    form: &[(&str, &str)],
+    context: &Context,
+) -> Result<Response, AocError> {
+    refuse_offline(url, context)?;
+    let client = context.client()?;
+    throttle(context).await?;
+    client
+        .post(url)
+        .form(form)
+        .send()
+        .await
+        .map_err(AocError::network(url))
+}
+
+fn refuse_offline(url: &str, context: &Context) -> Result<(), AocError> {
+    if context.offline {
+        Err(AocError::Offline {
+            url: url.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Waits until the minimum interval has passed since the last request made by any process
+/// sharing the data directory, then records the current time as the last request.
+///
+/// The timestamp is kept in a file that is locked while it is read and updated, so concurrent
+/// processes queue up behind one another.
+async fn throttle(context: &Context) -> Result<(), AocError> {
+    if context.http.min_interval.is_zero() {
+        return Ok(());
+    }
+
+    let dir = crate::data_root(context)?;
+    fs::create_dir_all(&dir).map_err(AocError::cache(&dir))?;
+    let path = dir.join(THROTTLE_FILE);
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .map_err(AocError::cache(&path))?;
+    lock(&file, &path).await?;
+
+    let wait = last_request(&mut file, &path)?
+        .map(|last| {
+            context
+                .http
+                .min_interval
+                .saturating_sub(elapsed_since(last))
+        })
+        .unwrap_or_default();
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    file.set_len(0)
+        .and_then(|_| file.seek(SeekFrom::Start(0)))
+        .and_then(|_| file.write_all(now.to_string().as_bytes()))
+        .map_err(AocError::cache(&path))
+    // the lock is released when the file is closed
+}
+
+async fn lock(file: &File, path: &Path) -> Result<(), AocError> {
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(()),
+            Err(std::fs::TryLockError::WouldBlock) => {
+                tokio::time::sleep(Duration::from_millis(20)).await
+            }
+            Err(std::fs::TryLockError::Error(e)) => return Err(AocError::cache(path)(e)),
+        }
+    }
+}
+
+fn last_request(file: &mut File, path: &Path) -> Result<Option<SystemTime>, AocError> {
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .map_err(AocError::cache(path))?;
+    Ok(contents
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis)))
+}
+
+fn elapsed_since(time: SystemTime) -> Duration {
+    SystemTime::now().duration_since(time).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_context;
+    use std::time::Instant;
+
+    fn build_context(url: &str, name: &str) -> Context {
+        test_context(url, std::env::temp_dir().join("aoc_http_test").join(name))
+    }
+
+    // this is not using tokio::test due to runtime block_on collisions when using mockito
+    #[test]
+    fn server_error_retried() {
+        let mut server = mockito::Server::new();
+        let failure = server
+            .mock("GET", "/flaky")
+            .with_status(502)
+            .expect(2)
+            .create();
+        let success = server
+            .mock("GET", "/flaky")
+            .with_status(200)
+            .with_body("ok")
+            .expect(1)
+            .create();
+
+        let mut context = build_context(&server.url(), "server_error_retried");
+        context.http.retries = 3;
+        context.http.backoff = Duration::from_millis(10);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let url = format!("{}flaky", context.url);
+        let response = runtime.block_on(get(&url, &context)).unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+        failure.assert();
+        success.assert();
+    }
+
+    #[test]
+    fn retries_bounded() {
+        let mut server = mockito::Server::new();
+        let failure = server
+            .mock("GET", "/down")
+            .with_status(503)
+            .expect(3)
+            .create();
+
+        let mut context = build_context(&server.url(), "retries_bounded");
+        context.http.retries = 2;
+        context.http.backoff = Duration::from_millis(10);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let url = format!("{}down", context.url);
+        let response = runtime.block_on(get(&url, &context)).unwrap();
+
+        assert_eq!(response.status().as_u16(), 503);
+        failure.assert();
+    }
+
+    #[test]
+    fn user_agent_sent() {
+        let mut server = mockito::Server::new();
+        let m = server
+            .mock("GET", "/")
+            .match_header("user-agent", "test-agent contact: someone@example.com")
+            .create();
+
+        let mut context = build_context(&server.url(), "user_agent_sent");
+        context.http.user_agent = "test-agent contact: someone@example.com".to_string();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime
+            .block_on(get(context.url.as_str(), &context))
+            .unwrap();
+        m.assert();
+    }
+
+    #[test]
+    fn contact_appended() {
+        let config = HttpConfig::default().with_contact("someone@example.com");
+        assert!(config.user_agent.starts_with(DEFAULT_USER_AGENT));
+        assert!(config.user_agent.ends_with("contact: someone@example.com"));
+    }
+
+    #[test]
+    fn requests_spaced_out() {
+        let mut context = build_context("https://adventofcode.com", "requests_spaced_out");
+        context.http.min_interval = Duration::from_millis(200);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let start = Instant::now();
+        runtime.block_on(throttle(&context)).unwrap();
+        runtime.block_on(throttle(&context)).unwrap();
+        runtime.block_on(throttle(&context)).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(400));
+        assert!(context.data_dir.join(THROTTLE_FILE).exists());
+    }
+}
@@ -0,0 +1,435 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A puzzle input, with the views the puzzles commonly need.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    raw: String,
+}
+
+/// How a solution wants its input normalized before it is parsed. The cache always keeps the exact
+/// bytes returned by the site, so whitespace-sensitive puzzles can choose to see all of it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Removes the line endings at the end of the input, keeping every other space.
+    #[default]
+    TrimTrailingNewline,
+    /// Removes whitespace at the start and end of the input.
+    TrimAll,
+    /// Keeps the input exactly as it was fetched.
+    Keep,
+}
+
+/// A failure to parse part of an input, located by its 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    /// The text that could not be parsed.
+    pub text: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: {} {:?}",
+            self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Describes text that could not be parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The 1-based line of the text.
+    /// * `column` - The 1-based column of the first character of the text.
+    /// * `text` - The text that could not be parsed.
+    /// * `reason` - What was expected instead.
+    pub fn new(
+        line: usize,
+        column: usize,
+        text: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ParseError {
+        ParseError {
+            line,
+            column,
+            text: text.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Describes part of a line that could not be parsed, turning the byte offset of the part
+    /// into the column of its first character.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The 1-based line number.
+    /// * `source` - The text of the line.
+    /// * `offset` - The byte offset in `source` where the part starts.
+    /// * `text` - The part that could not be parsed.
+    /// * `reason` - What was expected instead.
+    pub fn at(
+        line: usize,
+        source: &str,
+        offset: usize,
+        text: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ParseError {
+        let column = source
+            .get(..offset)
+            .map_or(offset, |before| before.chars().count());
+        ParseError::new(line, column + 1, text, reason)
+    }
+
+    /// Describes part of a line of an input that could not be parsed, for parsers that read lines
+    /// without their numbers such as those of `blocks`. The line is the first line of the input
+    /// with the text of `source`, which is the one a parser reading in order fails on first. A
+    /// `source` that is not a line of the input is placed at line 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input being parsed.
+    /// * `source` - The text of the line.
+    /// * `offset` - The byte offset in `source` where the part starts.
+    /// * `text` - The part that could not be parsed.
+    /// * `reason` - What was expected instead.
+    pub fn within(
+        input: &Input,
+        source: &str,
+        offset: usize,
+        text: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ParseError {
+        let line = input
+            .raw
+            .lines()
+            .position(|line| line == source)
+            .map_or(1, |row| row + 1);
+        ParseError::at(line, source, offset, text, reason)
+    }
+
+    /// Shows the error with the line of the input it was found on and a caret under the text.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input that was being parsed, normalized as the parser saw it.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The description, followed by the line and the caret when the line exists.
+    pub fn render(&self, input: &Input) -> String {
+        let Some(source) = self
+            .line
+            .checked_sub(1)
+            .and_then(|i| input.lines().get(i).copied())
+        else {
+            return format!("{} at the end of the input", self);
+        };
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}{}",
+            self,
+            gutter,
+            number,
+            source,
+            gutter,
+            " ".repeat(self.column.saturating_sub(1)),
+            "^".repeat(self.text.chars().count().max(1))
+        )
+    }
+}
+
+impl Input {
+    /// Wraps the text of an input.
+    pub fn new(raw: impl Into<String>) -> Input {
+        Input { raw: raw.into() }
+    }
+
+    /// Applies a normalization policy.
+    pub fn normalize(self, normalization: Normalization) -> Input {
+        match normalization {
+            Normalization::TrimTrailingNewline => {
+                Input::new(self.raw.trim_end_matches(['\n', '\r']))
+            }
+            Normalization::TrimAll => Input::new(self.raw.trim()),
+            Normalization::Keep => self,
+        }
+    }
+
+    /// The input exactly as it was read.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// The lines of the input, without their line endings. A final line ending does not start
+    /// another line.
+    pub fn lines(&self) -> Vec<&str> {
+        self.raw.lines().collect()
+    }
+
+    /// The groups of lines separated by blank lines, such as the sections of an input with a
+    /// header. Runs of blank lines never produce empty groups.
+    pub fn blocks(&self) -> Vec<Vec<&str>> {
+        let mut blocks = vec![];
+        let mut block = vec![];
+        for line in self.raw.lines() {
+            if line.trim().is_empty() {
+                if !block.is_empty() {
+                    blocks.push(std::mem::take(&mut block));
+                }
+            } else {
+                block.push(line);
+            }
+        }
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    /// Converts every character of every line into a cell of a grid, indexed by row and then by
+    /// column.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Vec<T>>)` - The rows of the grid.
+    /// * `Err(ParseError)` - The first character that is not a valid cell.
+    pub fn grid<T: TryFrom<char>>(&self) -> Result<Vec<Vec<T>>, ParseError> {
+        self.raw
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(column, c)| {
+                        T::try_from(c).map_err(|_| ParseError {
+                            line: row + 1,
+                            column: column + 1,
+                            text: c.to_string(),
+                            reason: "Unexpected character".to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Every integer in the input, in order. A `-` is read as a sign only when it does not follow
+    /// a digit, so ranges such as `11-22` yield `11` and `22`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<i64>)` - The integers.
+    /// * `Err(ParseError)` - The first integer that does not fit in an `i64`.
+    pub fn ints(&self) -> Result<Vec<i64>, ParseError> {
+        let mut ints = vec![];
+        for (row, line) in self.raw.lines().enumerate() {
+            let bytes = line.as_bytes();
+            let mut i = 0;
+            while i < bytes.len() {
+                let signed = bytes[i] == b'-'
+                    && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                    && (i == 0 || !bytes[i - 1].is_ascii_digit());
+                if !signed && !bytes[i].is_ascii_digit() {
+                    i += 1;
+                    continue;
+                }
+
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let text = &line[start..i];
+                ints.push(text.parse::<i64>().map_err(|_| ParseError {
+                    line: row + 1,
+                    column: start + 1,
+                    text: text.to_string(),
+                    reason: "Integer out of range".to_string(),
+                })?);
+            }
+        }
+        Ok(ints)
+    }
+
+    /// The characters of the input read top to bottom, one string per column. Whitespace is kept
+    /// exactly and lines shorter than the widest one are padded with spaces, so the columns of
+    /// right or left aligned numbers can be read as they are laid out.
+    pub fn columns(&self) -> Vec<String> {
+        let rows = self
+            .raw
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        (0..width)
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row.get(column).copied().unwrap_or(' '))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl From<String> for Input {
+    fn from(raw: String) -> Input {
+        Input::new(raw)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(raw: &str) -> Input {
+        Input::new(raw)
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Tile {
+        Empty,
+        Wall,
+    }
+
+    impl TryFrom<char> for Tile {
+        type Error = ();
+
+        fn try_from(c: char) -> Result<Tile, ()> {
+            match c {
+                '.' => Ok(Tile::Empty),
+                '#' => Ok(Tile::Wall),
+                _ => Err(()),
+            }
+        }
+    }
+
+    #[test]
+    fn lines_drop_final_newline() {
+        let input = Input::from("a\r\nb\n\nc\n");
+        assert_eq!(input.lines(), vec!["a", "b", "", "c"]);
+        assert_eq!(input.raw(), "a\r\nb\n\nc\n");
+        assert!(Input::from("").lines().is_empty());
+    }
+
+    #[test]
+    fn normalization_applied() {
+        let input = || Input::from("  1 2\n3  \r\n\n");
+        assert_eq!(
+            input().normalize(Normalization::TrimTrailingNewline).raw(),
+            "  1 2\n3  "
+        );
+        assert_eq!(input().normalize(Normalization::TrimAll).raw(), "1 2\n3");
+        assert_eq!(
+            input().normalize(Normalization::Keep).raw(),
+            "  1 2\n3  \r\n\n"
+        );
+    }
+
+    #[test]
+    fn blocks_split_on_blank_lines() {
+        let input = Input::from("3-5\n10-14\n\n  \n1\n5\n\n");
+        assert_eq!(input.blocks(), vec![vec!["3-5", "10-14"], vec!["1", "5"]]);
+    }
+
+    #[test]
+    fn grid_converted() {
+        let input = Input::from(".#\n#.\n");
+        assert_eq!(
+            input.grid::<Tile>().unwrap(),
+            vec![vec![Tile::Empty, Tile::Wall], vec![Tile::Wall, Tile::Empty]]
+        );
+        assert_eq!(
+            input.grid::<char>().unwrap(),
+            vec![vec!['.', '#'], vec!['#', '.']]
+        );
+    }
+
+    #[test]
+    fn grid_reports_position() {
+        let result = Input::from("..\n.x\n").grid::<Tile>().unwrap_err();
+        assert_eq!((result.line, result.column), (2, 2));
+        assert_eq!(result.text, "x");
+        assert_eq!(
+            result.to_string(),
+            "Line 2, column 2: Unexpected character \"x\""
+        );
+    }
+
+    #[test]
+    fn error_located_in_line() {
+        let line = "é68,X12";
+        let result = ParseError::at(3, line, 5, "X", "Unknown direction");
+        assert_eq!((result.line, result.column), (3, 5));
+        assert_eq!(result.text, "X");
+        assert_eq!(
+            ParseError::at(1, line, 0, "é", "Unknown direction").column,
+            1
+        );
+    }
+
+    #[test]
+    fn error_located_in_input() {
+        let input = Input::from("3-5\n\n10-1a\n");
+        let line = input.blocks()[1][0].to_string();
+        let result = ParseError::within(&input, &line, 3, "1a", "Expected a number");
+        assert_eq!((result.line, result.column), (3, 4));
+        assert_eq!(result.text, "1a");
+        let result = ParseError::within(&input, "1a", 0, "1a", "Expected a number");
+        assert_eq!((result.line, result.column), (1, 1));
+    }
+
+    #[test]
+    fn error_rendered_with_caret() {
+        let input = Input::from("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nX82 ");
+        let result = ParseError::new(10, 1, "X", "Unknown direction");
+        assert_eq!(
+            result.render(&input),
+            "Line 10, column 1: Unknown direction \"X\"\n   |\n10 | X82 \n   | ^"
+        );
+
+        let result = ParseError::new(2, 3, "", "Expected a number");
+        assert_eq!(
+            result.render(&Input::from("ab")),
+            "Line 2, column 3: Expected a number \"\" at the end of the input"
+        );
+    }
+
+    #[test]
+    fn ints_extracted() {
+        let input = Input::from("11-22,95-115\nx=-3, y=+4\n-7");
+        assert_eq!(input.ints().unwrap(), vec![11, 22, 95, 115, -3, 4, -7]);
+    }
+
+    #[test]
+    fn ints_overflow_reported() {
+        let result = Input::from("1\n 99999999999999999999").ints().unwrap_err();
+        assert_eq!((result.line, result.column), (2, 2));
+    }
+
+    #[test]
+    fn columns_keep_whitespace() {
+        let input = Input::from("123 328\n 45 64\n  6 98\n*   +  \n");
+        assert_eq!(
+            input.columns(),
+            vec!["1  *", "24  ", "356 ", "    ", "369+", "248 ", "8   "]
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate::batch::DayRun;
+use crate::AocError;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file of known answers, at the root of the workspace.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// How the default profile is written in the answers file.
+const DEFAULT_PROFILE: &str = "default";
+
+/// Finds the answers file the way a `.session` file is found, in `start` or the closest of its
+/// parents that has one, so the runner can be started from anywhere in the workspace.
+///
+/// # Arguments
+///
+/// * `start` - The directory to start searching from.
+///
+/// # Returns
+///
+/// * `PathBuf` - The answers file found, or where a new one is created in `start` if there is none.
+pub fn find_answers_file(start: &Path) -> PathBuf {
+    start
+        .ancestors()
+        .map(|dir| dir.join(ANSWERS_FILE))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| start.join(ANSWERS_FILE))
+}
+
+/// A confirmed answer to one part of a puzzle, for the input of one profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownAnswer {
+    pub year: i32,
+    pub day: i32,
+    pub part: i32,
+    /// The profile whose input was answered, `None` for the default profile.
+    pub profile: Option<String>,
+    pub answer: String,
+}
+
+/// The confirmed answers kept in a checked-in file, one tab separated line of year, day, part,
+/// profile and answer each. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug)]
+pub struct KnownAnswers {
+    path: PathBuf,
+    answers: Vec<KnownAnswer>,
+}
+
+impl KnownAnswers {
+    /// Loads the known answers, starting with none when the file does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The answers file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(KnownAnswers)` - The answers in the file.
+    /// * `Err(AocError)` - If the file cannot be read or a line is malformed.
+    pub fn load(path: &Path) -> Result<KnownAnswers, AocError> {
+        let io_error = |source| AocError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut answers = if path.exists() {
+            fs::read_to_string(path)
+                .map_err(io_error)?
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+                .map(|(i, line)| {
+                    parse_answer(line).ok_or_else(|| {
+                        io_error(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Malformed answer on line {}: {}", i + 1, line),
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![]
+        };
+        answers.sort_by_key(key);
+
+        Ok(KnownAnswers {
+            path: path.to_path_buf(),
+            answers,
+        })
+    }
+
+    /// The known answers of a profile, ordered by year, day and part.
+    pub fn answers<'a>(
+        &'a self,
+        profile: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a KnownAnswer> {
+        self.answers
+            .iter()
+            .filter(move |a| a.profile.as_deref() == profile)
+    }
+
+    /// The known answer to a part for the input of a profile.
+    pub fn get(&self, year: &i32, day: &i32, part: &i32, profile: Option<&str>) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|a| key(a) == (*year, *day, *part, profile.map(str::to_string)))
+            .map(|a| a.answer.as_str())
+    }
+
+    /// Records the confirmed answer to a part, replacing any earlier answer, and saves the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `known` - The confirmed answer.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - Whether the file changed.
+    /// * `Err(AocError)` - If the file cannot be written.
+    pub fn record(&mut self, known: KnownAnswer) -> Result<bool, AocError> {
+        match self.answers.iter_mut().find(|a| key(a) == key(&known)) {
+            Some(existing) if existing.answer == known.answer => return Ok(false),
+            Some(existing) => *existing = known,
+            None => self.answers.push(known),
+        }
+        self.answers.sort_by_key(key);
+
+        let body = self
+            .answers
+            .iter()
+            .map(|a| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\n",
+                    a.year,
+                    a.day,
+                    a.part,
+                    a.profile.as_deref().unwrap_or(DEFAULT_PROFILE),
+                    a.answer
+                )
+            })
+            .collect::<String>();
+        fs::write(&self.path, body).map_err(|source| AocError::Io {
+            path: self.path.clone(),
+            source,
+        })?;
+        Ok(true)
+    }
+}
+
+fn key(answer: &KnownAnswer) -> (i32, i32, i32, Option<String>) {
+    (answer.year, answer.day, answer.part, answer.profile.clone())
+}
+
+fn parse_answer(line: &str) -> Option<KnownAnswer> {
+    let fields = line.splitn(5, '\t').collect::<Vec<_>>();
+    let [year, day, part, profile, answer] = fields.as_slice() else {
+        return None;
+    };
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return None;
+    }
+
+    Some(KnownAnswer {
+        year: year.parse().ok()?,
+        day: day.parse().ok()?,
+        part: part.parse().ok()?,
+        profile: Some(profile.to_string()).filter(|p| p != DEFAULT_PROFILE),
+        answer: answer.to_string(),
+    })
+}
+
+/// The result of checking one known answer against a rerun of its solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub year: i32,
+    pub day: i32,
+    pub part: i32,
+    pub expected: String,
+    /// The answer computed by the rerun, `None` if the day was not run or did not finish.
+    pub actual: Option<String>,
+}
+
+impl Verification {
+    /// Whether the rerun produced the known answer.
+    pub fn passed(&self) -> bool {
+        self.actual.as_deref() == Some(self.expected.as_str())
+    }
+}
+
+/// Checks each known answer of a profile against the runs of its day.
+///
+/// # Arguments
+///
+/// * `known` - The known answers.
+/// * `profile` - The profile whose inputs were run, `None` for the default profile.
+/// * `runs` - The runs of the days to check. Known answers of days without a run are not checked.
+///
+/// # Returns
+///
+/// * `Vec<Verification>` - One verification per known answer of the days that were run.
+pub fn verify(known: &KnownAnswers, profile: Option<&str>, runs: &[DayRun]) -> Vec<Verification> {
+    known
+        .answers(profile)
+        .filter_map(|k| {
+            let run = runs.iter().find(|r| r.year == k.year && r.day == k.day)?;
+            let actual = run.solved().and_then(|solved| match k.part {
+                1 => solved.part1.submission(),
+                2 => solved.part2.submission(),
+                _ => None,
+            });
+            Some(Verification {
+                year: k.year,
+                day: k.day,
+                part: k.part,
+                expected: k.answer.clone(),
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// Renders one line per verification, marking the ones that did not produce their known answer.
+pub fn render_verifications(verifications: &[Verification]) -> String {
+    verifications
+        .iter()
+        .map(|v| {
+            let result = match &v.actual {
+                _ if v.passed() => format!("ok        {}", v.expected),
+                Some(actual) => format!("MISMATCH  expected {}, got {}", v.expected, actual),
+                None => format!("FAILED    expected {}, no answer was produced", v.expected),
+            };
+            format!(
+                "AOC {} Day {:>2} part {}  {}\n",
+                v.year, v.day, v.part, result
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::{run_day, Status};
+    use crate::solution::Registration;
+    use crate::{Input, ParseError, Solution};
+
+    struct Count;
+
+    impl Solution for Count {
+        type Parsed = Vec<i64>;
+        type Part1 = usize;
+        type Part2 = &'static str;
+
+        fn parse(input: &Input) -> Result<Vec<i64>, ParseError> {
+            input.ints()
+        }
+
+        fn part1(parsed: &Vec<i64>) -> usize {
+            parsed.len()
+        }
+
+        fn part2(_parsed: &Vec<i64>) -> &'static str {
+            "XYZ"
+        }
+    }
+
+    fn answers_file(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("aoc_known_test");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn answers_loaded_by_profile() {
+        let path = answers_file(
+            "loaded.txt",
+            "# confirmed answers\n2025\t1\t1\tdefault\t982\n\n2025\t1\t1\twork\t1010\n2025\t3\t2\tdefault\tEZ FCH\n",
+        );
+        let known = KnownAnswers::load(&path).unwrap();
+        assert_eq!(known.get(&2025, &1, &1, None), Some("982"));
+        assert_eq!(known.get(&2025, &1, &1, Some("work")), Some("1010"));
+        assert_eq!(known.get(&2025, &3, &2, None), Some("EZ FCH"));
+        assert_eq!(known.get(&2025, &1, &2, None), None);
+        assert_eq!(known.answers(None).count(), 2);
+
+        let path = answers_file("malformed.txt", "2025\t1\tone\tdefault\t982\n");
+        assert!(matches!(
+            KnownAnswers::load(&path),
+            Err(AocError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn answers_file_found_in_parents() {
+        let workspace = std::env::temp_dir()
+            .join("aoc_known_test")
+            .join("workspace");
+        let nested = workspace.join("crates/day1");
+        fs::create_dir_all(&nested).unwrap();
+        let _ = fs::remove_file(workspace.join(ANSWERS_FILE));
+
+        assert_eq!(find_answers_file(&nested), nested.join(ANSWERS_FILE));
+        fs::write(workspace.join(ANSWERS_FILE), "").unwrap();
+        assert_eq!(find_answers_file(&nested), workspace.join(ANSWERS_FILE));
+    }
+
+    #[test]
+    fn answers_recorded_in_order() {
+        let path = answers_file("recorded.txt", "2025\t4\t1\tdefault\t1428\n");
+        let mut known = KnownAnswers::load(&path).unwrap();
+        let answer = |day, answer: &str| KnownAnswer {
+            year: 2025,
+            day,
+            part: 1,
+            profile: None,
+            answer: answer.to_string(),
+        };
+
+        assert!(known.record(answer(2, "19605500130")).unwrap());
+        assert!(!known.record(answer(2, "19605500130")).unwrap());
+        assert!(known.record(answer(4, "1429")).unwrap());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "2025\t2\t1\tdefault\t19605500130\n2025\t4\t1\tdefault\t1429\n"
+        );
+        assert_eq!(
+            KnownAnswers::load(&path).unwrap().get(&2025, &4, &1, None),
+            Some("1429")
+        );
+    }
+
+    #[test]
+    fn mismatches_reported() {
+        let path = answers_file(
+            "verified.txt",
+            "1003\t1\t1\tdefault\t3\n1003\t1\t2\tdefault\tXYZ\n1003\t2\t1\tdefault\t4\n1003\t9\t1\tdefault\t1\n",
+        );
+        let known = KnownAnswers::load(&path).unwrap();
+        let runs = vec![
+            run_day(
+                &Registration::new::<Count>(1003, 1, "Count"),
+                Ok(Input::from("1 2 3")),
+            ),
+            run_day(
+                &Registration::new::<Count>(1003, 2, "Count"),
+                Ok(Input::from("1 2 3")),
+            ),
+        ];
+        assert!(matches!(runs[0].status, Status::Solved(_)));
+
+        let verifications = verify(&known, None, &runs);
+        assert_eq!(verifications.len(), 3);
+        assert!(verifications[0].passed() && verifications[1].passed());
+        assert!(!verifications[2].passed());
+        assert_eq!(
+            render_verifications(&verifications[2..]),
+            "AOC 1003 Day  2 part 1  MISMATCH  expected 4, got 3\n"
+        );
+    }
+}
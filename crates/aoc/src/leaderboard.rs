@@ -0,0 +1,329 @@
+use crate::unlock::{self, format_countdown};
+use crate::{cache, ensure_data_dir, error, http, AocError, Context};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The site asks that private leaderboards are fetched no more than once every 15 minutes.
+pub const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// A private leaderboard as returned by `/{year}/leaderboard/private/view/{id}.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    /// Members keyed by their account id.
+    pub members: BTreeMap<String, Member>,
+}
+
+/// A member of a private leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// The display name, missing for anonymous users.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    pub global_score: u64,
+    /// When the most recent star was earned, in seconds since the Unix epoch.
+    pub last_star_ts: u64,
+    /// The stars earned, keyed by day and then by part.
+    pub completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+/// A star earned by completing one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Star {
+    /// When the star was earned, in seconds since the Unix epoch.
+    pub get_star_ts: u64,
+    pub star_index: u64,
+}
+
+impl Member {
+    /// The name shown on the site, which falls back to the account id for anonymous users.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    /// The star earned for a part of a day, if the member has completed it.
+    pub fn star(&self, day: &i32, part: &i32) -> Option<&Star> {
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(&part.to_string())
+    }
+}
+
+impl Star {
+    /// The moment the star was earned.
+    pub fn earned(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.get_star_ts)
+    }
+}
+
+impl Leaderboard {
+    /// The members ordered as the site ranks them: by local score, then by stars, then by who
+    /// earned their last star first.
+    pub fn standings(&self) -> Vec<&Member> {
+        let mut members = self.members.values().collect::<Vec<_>>();
+        members.sort_by(|a, b| {
+            b.local_score
+                .cmp(&a.local_score)
+                .then(b.stars.cmp(&a.stars))
+                .then(a.last_star_ts.cmp(&b.last_star_ts))
+        });
+        members
+    }
+
+    /// The members with at least one star on a day, ordered by when they completed part one.
+    pub fn day_timeline(&self, day: &i32) -> Vec<&Member> {
+        let mut members = self
+            .members
+            .values()
+            .filter(|m| m.star(day, &1).is_some())
+            .collect::<Vec<_>>();
+        members.sort_by_key(|m| m.star(day, &1).map(|s| s.get_star_ts));
+        members
+    }
+}
+
+/// Fetches a private leaderboard, using the cached copy while it is less than 15 minutes old.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `id` - The id of the private leaderboard, which is the account id of its owner.
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(Leaderboard)` - The leaderboard.
+/// * `Err(AocError)` - If there is an error fetching, parsing or caching the leaderboard.
+pub async fn get_leaderboard(
+    year: &i32,
+    id: &u64,
+    context: &Context,
+) -> Result<Leaderboard, AocError> {
+    let file = ensure_data_dir(context)?.join(build_file_name(year, id));
+    let path = format!(
+        "{}{}/leaderboard/private/view/{}.json",
+        context.url, year, id
+    );
+
+    let fresh = fs::metadata(&file)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| modified.elapsed().unwrap_or_default() < CACHE_TTL)
+        .unwrap_or(false);
+    if fresh {
+        println!("Cache hit");
+        let body = fs::read_to_string(&file).map_err(AocError::cache(&file))?;
+        return parse_leaderboard(&body, &path);
+    }
+
+    println!("Fetching leaderboard from {}", path);
+    let response = http::get(&path, context).await?;
+    let body = error::read_body(response, year, None).await?;
+    let leaderboard = parse_leaderboard(&body, &path)?;
+    cache::write_atomic(&file, &body)?;
+
+    Ok(leaderboard)
+}
+
+/// Parses the JSON of a private leaderboard.
+///
+/// # Arguments
+///
+/// * `body` - The JSON returned by the site.
+/// * `url` - The address the JSON was fetched from, used to report failures.
+///
+/// # Returns
+///
+/// * `Ok(Leaderboard)` - The parsed leaderboard.
+/// * `Err(AocError)` - `Unauthorized` if the site returned its log in page, otherwise
+///   `MalformedResponse`.
+pub fn parse_leaderboard(body: &str, url: &str) -> Result<Leaderboard, AocError> {
+    serde_json::from_str(body).map_err(|e| {
+        // a signed out visitor is redirected to a page offering to log in
+        if body.contains("/auth/login") {
+            AocError::Unauthorized {
+                url: url.to_string(),
+            }
+        } else {
+            AocError::MalformedResponse {
+                url: url.to_string(),
+                reason: format!("The leaderboard could not be parsed: {}", e),
+            }
+        }
+    })
+}
+
+/// Renders the standings as a ranked table of score, stars and name.
+pub fn render_standings(leaderboard: &Leaderboard) -> String {
+    leaderboard
+        .standings()
+        .iter()
+        .enumerate()
+        .map(|(i, member)| {
+            format!(
+                "{:>3}) {:>5} {:>3}* {}\n",
+                i + 1,
+                member.local_score,
+                member.stars,
+                member.display_name()
+            )
+        })
+        .collect()
+}
+
+/// Renders how long after the puzzle unlocked each member earned their stars on a day.
+///
+/// # Arguments
+///
+/// * `leaderboard` - The leaderboard to render.
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+///
+/// # Returns
+///
+/// * `Ok(String)` - One line per member with a star on the day, in the order part one was completed.
+/// * `Err(AocError)` - If the event has no puzzle for that year and day.
+pub fn render_timeline(
+    leaderboard: &Leaderboard,
+    year: &i32,
+    day: &i32,
+) -> Result<String, AocError> {
+    let unlocked = unlock::unlock_time(year, day)?;
+    let since_unlock = |star: Option<&Star>| match star {
+        Some(star) => format_countdown(&star.earned().duration_since(unlocked).unwrap_or_default()),
+        None => "-".to_string(),
+    };
+
+    Ok(leaderboard
+        .day_timeline(day)
+        .iter()
+        .map(|member| {
+            format!(
+                "{:>12} {:>12}  {}\n",
+                since_unlock(member.star(day, &1)),
+                since_unlock(member.star(day, &2)),
+                member.display_name()
+            )
+        })
+        .collect())
+}
+
+fn build_file_name(year: &i32, id: &u64) -> PathBuf {
+    PathBuf::from(year.to_string()).join(format!("leaderboard.{}.json", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_context;
+
+    fn get_fixture() -> Leaderboard {
+        parse_leaderboard(include_str!("fixtures/leaderboard.json"), "fixture").unwrap()
+    }
+
+    fn build_context(url: &str, name: &str) -> Context {
+        let data_dir = std::env::temp_dir().join("aoc_leaderboard_test").join(name);
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir).unwrap();
+        }
+        test_context(url, data_dir)
+    }
+
+    #[test]
+    fn fixture_parsed() {
+        let result = get_fixture();
+        assert_eq!(result.owner_id, 101);
+        assert_eq!(result.members.len(), 3);
+
+        let alice = &result.members["101"];
+        assert_eq!(alice.display_name(), "Alice");
+        assert_eq!(alice.star(&1, &2).unwrap().get_star_ts, 1764566700);
+        assert_eq!(alice.star(&2, &2), None);
+        assert_eq!(
+            result.members["303"].display_name(),
+            "(anonymous user #303)"
+        );
+    }
+
+    #[test]
+    fn standings_ranked_by_score() {
+        let result = get_fixture();
+        let names = result.standings().iter().map(|m| m.id).collect::<Vec<_>>();
+        assert_eq!(names, vec![101, 202, 303]);
+        assert_eq!(
+            render_standings(&result),
+            "  1)    11   3* Alice\n  2)     5   2* Bob\n  3)     0   0* (anonymous user #303)\n"
+        );
+    }
+
+    #[test]
+    fn timeline_relative_to_unlock() {
+        let result = render_timeline(&get_fixture(), &2025, &1).unwrap();
+        assert_eq!(
+            result,
+            "    00:12:34     00:25:00  Alice\n    01:00:00     02:00:00  Bob\n"
+        );
+
+        let result = render_timeline(&get_fixture(), &2025, &2).unwrap();
+        assert_eq!(result, "    00:20:00            -  Alice\n");
+    }
+
+    #[test]
+    fn login_page_unauthorized() {
+        let result = parse_leaderboard("<a href=\"/auth/login\">[Log In]</a>", "url");
+        assert!(matches!(result, Err(AocError::Unauthorized { .. })));
+        let result = parse_leaderboard("{}", "url");
+        assert!(matches!(result, Err(AocError::MalformedResponse { .. })));
+    }
+
+    // this is not using tokio::test due to runtime block_on collisions when using mockito
+    #[test]
+    fn test_get_leaderboard() {
+        let mut server = mockito::Server::new();
+        let m = server
+            .mock("GET", "/2025/leaderboard/private/view/101.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(include_str!("fixtures/leaderboard.json"))
+            .expect(1)
+            .create();
+
+        let context = build_context(&server.url(), "test_get_leaderboard");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let fetched = runtime
+            .block_on(get_leaderboard(&2025, &101, &context))
+            .unwrap();
+        let cached = runtime
+            .block_on(get_leaderboard(&2025, &101, &context))
+            .unwrap();
+
+        assert_eq!(fetched, get_fixture());
+        assert_eq!(cached, fetched);
+        m.assert();
+    }
+
+    #[test]
+    fn missing_leaderboard_not_a_day() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/2025/leaderboard/private/view/404.json")
+            .with_status(404)
+            .with_body("Please don't repeatedly request this endpoint before it unlocks!")
+            .create();
+
+        let context = build_context(&server.url(), "missing_leaderboard_not_a_day");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_leaderboard(&2025, &404, &context));
+
+        assert!(matches!(result, Err(AocError::Http { status: 404, .. })));
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{cache, ensure_data_dir, AocError, Context, Verdict};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A single answer submitted for a part and the verdict the site returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+    pub part: i32,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// The reason an answer was withheld rather than submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    Unsolved,
+    Unrecordable,
+    AlreadySolved { answer: String },
+    Repeated { verdict: Verdict },
+    AboveTooHigh { bound: String },
+    BelowTooLow { bound: String },
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Unsolved => write!(f, "The part has not been solved yet"),
+            Refusal::Unrecordable => write!(f, "Answer contains a tab or a line break"),
+            Refusal::AlreadySolved { answer } => {
+                write!(f, "Part already solved with {}", answer)
+            }
+            Refusal::Repeated { verdict } => {
+                write!(f, "Answer was already submitted: {}", verdict)
+            }
+            Refusal::AboveTooHigh { bound } => {
+                write!(f, "Answer is not below {}, which was too high", bound)
+            }
+            Refusal::BelowTooLow { bound } => {
+                write!(f, "Answer is not above {}, which was too low", bound)
+            }
+        }
+    }
+}
+
+/// The persistent record of every answer submitted for a single puzzle.
+#[derive(Debug)]
+pub struct Ledger {
+    path: PathBuf,
+    guesses: Vec<Guess>,
+}
+
+impl Ledger {
+    /// Loads the ledger for the specified year and day, starting an empty one when none exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year of the event.
+    /// * `day` - The day of the event.
+    /// * `context` - Defines the context whose data directory holds the ledger.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Ledger)` - If the ledger is read successfully or does not exist yet.
+    /// * `Err(AocError)` - If the ledger file cannot be read or contains a malformed entry.
+    pub fn load(year: &i32, day: &i32, context: &Context) -> Result<Ledger, AocError> {
+        let path = ensure_data_dir(context)?.join(build_file_name(year, day));
+
+        let guesses = if path.exists() {
+            fs::read_to_string(&path)
+                .map_err(AocError::cache(&path))?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(parse_guess)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|reason| AocError::Cache {
+                    path: path.clone(),
+                    source: io::Error::new(io::ErrorKind::InvalidData, reason),
+                })?
+        } else {
+            vec![]
+        };
+
+        Ok(Ledger { path, guesses })
+    }
+
+    /// The answers recorded for the specified part, oldest first.
+    pub fn guesses(&self, part: &i32) -> impl Iterator<Item = &Guess> {
+        let part = *part;
+        self.guesses.iter().filter(move |g| g.part == part)
+    }
+
+    /// Checks whether an answer is worth submitting given the earlier verdicts for the part.
+    ///
+    /// # Arguments
+    ///
+    /// * `part` - The part of the puzzle being answered.
+    /// * `answer` - A string slice that holds the candidate answer.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If nothing in the ledger rules the answer out.
+    /// * `Err(Refusal)` - If the answer cannot be recorded, repeats an earlier guess or lies outside
+    ///   the known bounds.
+    pub fn check(&self, part: &i32, answer: &str) -> Result<(), Refusal> {
+        let answer = answer.trim();
+        if !is_recordable(answer) {
+            return Err(Refusal::Unrecordable);
+        }
+
+        if let Some(solved) = self.guesses(part).find(|g| g.verdict == Verdict::Correct) {
+            return Err(Refusal::AlreadySolved {
+                answer: solved.answer.clone(),
+            });
+        }
+
+        if let Some(repeat) = self
+            .guesses(part)
+            .find(|g| g.answer == answer && is_wrong(&g.verdict))
+        {
+            return Err(Refusal::Repeated {
+                verdict: repeat.verdict.clone(),
+            });
+        }
+
+        let value = match answer.parse::<i128>() {
+            Ok(value) => value,
+            Err(_) => return Ok(()),
+        };
+
+        let lowest_too_high = self
+            .bounds(part, Verdict::TooHigh)
+            .min_by_key(|(bound, _)| *bound);
+        if let Some((bound, text)) = lowest_too_high {
+            if value >= bound {
+                return Err(Refusal::AboveTooHigh {
+                    bound: text.to_string(),
+                });
+            }
+        }
+
+        let highest_too_low = self
+            .bounds(part, Verdict::TooLow)
+            .max_by_key(|(bound, _)| *bound);
+        if let Some((bound, text)) = highest_too_low {
+            if value <= bound {
+                return Err(Refusal::BelowTooLow {
+                    bound: text.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records the verdict for a submitted answer and persists the ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `part` - The part of the puzzle that was answered.
+    /// * `answer` - A string slice that holds the submitted answer.
+    /// * `verdict` - The verdict returned by the site.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the ledger is written successfully.
+    /// * `Err(AocError)` - If the answer would break the ledger apart or there is an error writing
+    ///   the ledger.
+    pub fn record(&mut self, part: &i32, answer: &str, verdict: &Verdict) -> Result<(), AocError> {
+        let answer = answer.trim();
+        if !is_recordable(answer) {
+            return Err(AocError::Refused {
+                answer: answer.to_string(),
+                refusal: Refusal::Unrecordable,
+            });
+        }
+
+        self.guesses.push(Guess {
+            part: *part,
+            answer: answer.to_string(),
+            verdict: verdict.clone(),
+        });
+
+        let body = self
+            .guesses
+            .iter()
+            .map(format_guess)
+            .collect::<Vec<_>>()
+            .join("\n");
+        cache::write_atomic(&self.path, &(body + "\n"))
+    }
+
+    fn bounds<'a>(&'a self, part: &i32, verdict: Verdict) -> impl Iterator<Item = (i128, &'a str)> {
+        self.guesses(part)
+            .filter(move |g| g.verdict == verdict)
+            .filter_map(|g| {
+                g.answer
+                    .parse::<i128>()
+                    .ok()
+                    .map(|v| (v, g.answer.as_str()))
+            })
+    }
+}
+
+fn is_wrong(verdict: &Verdict) -> bool {
+    matches!(verdict, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+}
+
+/// Whether an answer fits in one field of a ledger line, which is delimited by tabs and newlines.
+fn is_recordable(answer: &str) -> bool {
+    !answer.contains(['\t', '\n', '\r'])
+}
+
+/// Builds the path of the ledger relative to the data directory based on the year and day.
+fn build_file_name(year: &i32, day: &i32) -> PathBuf {
+    cache::day_dir(year, day).join(cache::LEDGER_FILE)
+}
+
+fn format_guess(guess: &Guess) -> String {
+    let verdict = match &guess.verdict {
+        Verdict::Correct => "correct".to_string(),
+        Verdict::TooHigh => "too_high".to_string(),
+        Verdict::TooLow => "too_low".to_string(),
+        Verdict::Wrong => "wrong".to_string(),
+        Verdict::RateLimited { wait } => format!("rate_limited:{}", wait.as_secs()),
+        Verdict::AlreadySolved => "already_solved".to_string(),
+        Verdict::WrongLevel => "wrong_level".to_string(),
+    };
+    format!("{}\t{}\t{}", guess.part, verdict, guess.answer)
+}
+
+fn parse_guess(line: &str) -> Result<Guess, String> {
+    let fields = line.splitn(3, '\t').collect::<Vec<_>>();
+    if fields.len() != 3 {
+        return Err(format!("Malformed ledger entry: {}", line));
+    }
+
+    let part = fields[0]
+        .parse::<i32>()
+        .map_err(|_| format!("Malformed ledger part: {}", line))?;
+    let verdict = match fields[1].split_once(':') {
+        Some(("rate_limited", wait)) => Verdict::RateLimited {
+            wait: Duration::from_secs(wait.parse().unwrap_or_default()),
+        },
+        _ => match fields[1] {
+            "correct" => Verdict::Correct,
+            "too_high" => Verdict::TooHigh,
+            "too_low" => Verdict::TooLow,
+            "wrong" => Verdict::Wrong,
+            "already_solved" => Verdict::AlreadySolved,
+            "wrong_level" => Verdict::WrongLevel,
+            other => return Err(format!("Unknown ledger verdict: {}", other)),
+        },
+    };
+
+    Ok(Guess {
+        part,
+        answer: fields[2].to_string(),
+        verdict,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_context;
+
+    fn build_context(name: &str) -> Context {
+        let data_dir = std::env::temp_dir().join("aoc_ledger_test").join(name);
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir).unwrap();
+        }
+        test_context("https://adventofcode.com", data_dir)
+    }
+
+    #[test]
+    fn empty_when_missing() {
+        let context = build_context("empty_when_missing");
+        let ledger = Ledger::load(&2025, &1, &context).unwrap();
+        assert_eq!(ledger.guesses(&1).count(), 0);
+        assert_eq!(ledger.check(&1, "42"), Ok(()));
+    }
+
+    #[test]
+    fn recorded_guesses_persist() {
+        let context = build_context("recorded_guesses_persist");
+        let mut ledger = Ledger::load(&2025, &2, &context).unwrap();
+        ledger.record(&2, "36862281460", &Verdict::TooHigh).unwrap();
+        ledger
+            .record(
+                &2,
+                "100",
+                &Verdict::RateLimited {
+                    wait: Duration::from_secs(30),
+                },
+            )
+            .unwrap();
+
+        let reloaded = Ledger::load(&2025, &2, &context).unwrap();
+        assert_eq!(
+            reloaded.guesses(&2).cloned().collect::<Vec<_>>(),
+            ledger.guesses(&2).cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(reloaded.guesses(&1).count(), 0);
+    }
+
+    #[test]
+    fn repeated_wrong_answer_refused() {
+        let context = build_context("repeated_wrong_answer_refused");
+        let mut ledger = Ledger::load(&2025, &3, &context).unwrap();
+        ledger.record(&1, "abc", &Verdict::Wrong).unwrap();
+
+        assert_eq!(
+            ledger.check(&1, "abc"),
+            Err(Refusal::Repeated {
+                verdict: Verdict::Wrong
+            })
+        );
+        assert_eq!(ledger.check(&2, "abc"), Ok(()));
+    }
+
+    #[test]
+    fn answers_outside_window_refused() {
+        let context = build_context("answers_outside_window_refused");
+        let mut ledger = Ledger::load(&2025, &3, &context).unwrap();
+        ledger
+            .record(&2, "173843907657110", &Verdict::TooLow)
+            .unwrap();
+        ledger
+            .record(&2, "173960689460215", &Verdict::TooHigh)
+            .unwrap();
+
+        assert_eq!(
+            ledger.check(&2, "173960689460216"),
+            Err(Refusal::AboveTooHigh {
+                bound: "173960689460215".to_string()
+            })
+        );
+        assert_eq!(
+            ledger.check(&2, "173843907657000"),
+            Err(Refusal::BelowTooLow {
+                bound: "173843907657110".to_string()
+            })
+        );
+        assert_eq!(ledger.check(&2, "173900000000000"), Ok(()));
+    }
+
+    #[test]
+    fn solved_part_refused() {
+        let context = build_context("solved_part_refused");
+        let mut ledger = Ledger::load(&2025, &1, &context).unwrap();
+        ledger.record(&1, "1097", &Verdict::Correct).unwrap();
+
+        assert_eq!(
+            ledger.check(&1, "1098"),
+            Err(Refusal::AlreadySolved {
+                answer: "1097".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn delimiters_in_answer_refused() {
+        let context = build_context("delimiters_in_answer_refused");
+        let mut ledger = Ledger::load(&2025, &6, &context).unwrap();
+
+        assert_eq!(ledger.check(&1, "EZ\tFCH"), Err(Refusal::Unrecordable));
+        assert_eq!(ledger.check(&1, "EZ\nFCH"), Err(Refusal::Unrecordable));
+        assert!(ledger.record(&1, "EZ\nFCH", &Verdict::Wrong).is_err());
+        ledger.record(&1, "EZ FCH\n", &Verdict::Wrong).unwrap();
+
+        let reloaded = Ledger::load(&2025, &6, &context).unwrap();
+        assert_eq!(
+            reloaded
+                .guesses(&1)
+                .map(|g| g.answer.as_str())
+                .collect::<Vec<_>>(),
+            ["EZ FCH"]
+        );
+    }
+
+    #[test]
+    fn malformed_entry_rejected() {
+        let context = build_context("malformed_entry_rejected");
+        cache::write_atomic(
+            &context.data_dir.join(build_file_name(&2025, &1)),
+            "1\tmaybe\t42\n",
+        )
+        .unwrap();
+
+        assert!(Ledger::load(&2025, &1, &context).is_err());
+    }
+}
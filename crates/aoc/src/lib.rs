@@ -1,19 +1,114 @@
+mod answer;
+pub mod batch;
+pub mod bench;
+pub mod cache;
+pub mod crypt;
+mod error;
+mod html;
+mod http;
+mod input;
+pub mod known;
+pub mod leaderboard;
+pub mod ledger;
+pub mod prefetch;
+pub mod puzzle;
+pub mod sample;
 pub mod session;
+pub mod solution;
 mod stats;
+pub mod submit;
+pub mod unlock;
+
+pub use answer::Answer;
+pub use crypt::CacheKey;
+pub use error::AocError;
+pub use http::HttpConfig;
+pub use input::{Input, Normalization, ParseError};
+pub use solution::Solution;
+pub use submit::{submit_answer, Verdict};
+
+#[doc(hidden)]
+pub use ctor as __ctor;
 
 use reqwest::{Client, Url};
 use std::fs;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::OnceLock;
 
 pub struct Context {
     pub session_id: String,
+    /// The named profile the session belongs to, `None` for the default profile.
+    pub profile: Option<String>,
     pub data_dir: PathBuf,
     pub url: Url,
+    /// How requests are made, read when the client is first used.
+    pub http: HttpConfig,
+    /// Refuses every request to the site so only cached files are used.
+    pub offline: bool,
+    /// Encrypts inputs written to the cache when set, and decrypts the encrypted ones read from it.
+    pub key: Option<CacheKey>,
+    client: OnceLock<Client>,
+}
+
+impl Context {
+    /// Creates a context for the default profile with the default HTTP settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_id` - The session cookie used to authenticate with the site.
+    /// * `data_dir` - The directory holding the cache, relative to the current directory.
+    /// * `url` - The root of the Advent of Code website.
+    pub fn new(session_id: String, data_dir: PathBuf, url: Url) -> Context {
+        Context {
+            session_id,
+            profile: None,
+            data_dir,
+            url,
+            http: HttpConfig::default(),
+            offline: false,
+            key: None,
+            client: OnceLock::new(),
+        }
+    }
+
+    /// Selects the named profile the session belongs to, `None` for the default profile.
+    pub fn with_profile(mut self, profile: Option<String>) -> Context {
+        self.profile = profile;
+        self
+    }
+
+    /// Replaces the HTTP settings.
+    pub fn with_http(mut self, http: HttpConfig) -> Context {
+        self.http = http;
+        self
+    }
+
+    /// Refuses every request to the site when `offline` is set, so only cached files are used.
+    pub fn with_offline(mut self, offline: bool) -> Context {
+        self.offline = offline;
+        self
+    }
+
+    /// Sets the key inputs are encrypted with in the cache, `None` to cache them in plaintext.
+    pub fn with_key(mut self, key: Option<CacheKey>) -> Context {
+        self.key = key;
+        self
+    }
+
+    /// The client shared by every request made with this context, built on first use.
+    pub(crate) fn client(&self) -> Result<&Client, AocError> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
+        let client = http::build_client(self)?;
+        Ok(self.client.get_or_init(|| client))
+    }
 }
 
 /// Fetches the input for the specified year and day.
 ///
+/// When the context is offline a missing input is reported as `NotCached` rather than fetched.
+///
 /// # Arguments
 ///
 /// * `year` - A string slice that holds the year of the event.
@@ -22,26 +117,31 @@ pub struct Context {
 ///
 /// # Returns
 ///
-/// * `Ok(Vec<String>)` - If the input is fetched and parsed successfully.
-/// * `Err(String)` - If there is an error fetching or parsing the input.
-pub async fn get_input(year: &i32, day: &i32, context: &Context) -> Result<Vec<String>, String> {
+/// * `Ok(Input)` - If the input is fetched successfully.
+/// * `Err(AocError)` - If there is an error fetching or caching the input.
+pub async fn get_input(year: &i32, day: &i32, context: &Context) -> Result<Input, AocError> {
     println!("Fetching input from for AOC {} Day {}", year, day);
 
-    let body = match get_input_from_cache(&year, &day, &context) {
+    let body = match get_input_from_cache(year, day, context)? {
         Some(body) => body,
+        None if context.offline => {
+            return Err(AocError::NotCached {
+                year: *year,
+                day: *day,
+                path: ensure_data_dir(context)?.join(build_file_name(year, day)),
+            })
+        }
         None => {
-            let body = get_input_from_site(year, day, &context).await?;
-            add_to_cache(year, day, &body, &context)?;
+            let body = get_input_from_site(year, day, context).await?;
+            add_to_cache(year, day, &body, context)?;
             body
         }
     };
 
-    let result: Vec<String> = body.split("\n").map(|s| s.to_string()).collect();
-
-    Ok(result)
+    Ok(Input::new(body))
 }
 
-/// Builds the file name for the input data based on the year and day.
+/// Builds the path of the input data based on the year and day.
 ///
 /// # Arguments
 ///
@@ -50,12 +150,13 @@ pub async fn get_input(year: &i32, day: &i32, context: &Context) -> Result<Vec<S
 ///
 /// # Returns
 ///
-/// * `String` - The constructed file name in the format `year.day.dat`.
-fn build_file_name(year: &i32, day: &i32) -> String {
-    format!("{}.day{}.dat", year, day)
+/// * `PathBuf` - The path relative to the data directory in the format `year/dayNN/input.txt`.
+pub(crate) fn build_file_name(year: &i32, day: &i32) -> PathBuf {
+    cache::day_dir(year, day).join(cache::INPUT_FILE)
 }
 
-/// Retrieves the input from the cache if it exists.
+/// Retrieves the input from the cache if it exists, checking it against the hash recorded when it
+/// was fetched. An encrypted input is decrypted with the key of the context.
 ///
 /// # Arguments
 ///
@@ -66,23 +167,27 @@ fn build_file_name(year: &i32, day: &i32) -> String {
 ///
 /// * `Ok(Some(String))` - If the input file exists and is read successfully.
 /// * `Ok(None)` - If the input file does not exist.
-/// * `Err(String)` - If there is an error reading the input file or creating the directory.
-pub fn get_input_from_cache(year: &i32, day: &i32, context: &Context) -> Option<String> {
-    let input_dir = std::env::current_dir().unwrap().join(&context.data_dir);
-    if !input_dir.exists() {
-        fs::create_dir(&input_dir).unwrap();
-    }
-
+/// * `Err(AocError)` - If there is an error reading the input file or creating the directory, the
+///   input cannot be decrypted, or it no longer matches its recorded hash.
+pub fn get_input_from_cache(
+    year: &i32,
+    day: &i32,
+    context: &Context,
+) -> Result<Option<String>, AocError> {
+    let input_dir = ensure_data_dir(context)?;
     let input_file = input_dir.join(build_file_name(year, day));
-    return if input_file.exists() {
-        println!("Cache hit");
-        let body = fs::read_to_string(input_file).unwrap();
-        Some(body)
-    } else {
-        None
+    let body = match crypt::read_encrypted(&input_file, context.key.as_ref())? {
+        Some(body) => Some(body),
+        None => cache::read_verified(&input_file)?,
     };
+    if body.is_some() {
+        println!("Cache hit");
+    }
+    Ok(body)
 }
-/// Cache the input data for later recall.
+
+/// Cache the input data for later recall, along with a sidecar recording where it came from. The
+/// input is encrypted when the context has a key.
 ///
 /// # Arguments
 ///
@@ -93,19 +198,56 @@ pub fn get_input_from_cache(year: &i32, day: &i32, context: &Context) -> Option<
 /// # Returns
 ///
 /// * `Ok(())` - If the input is successfully written to the cache.
-/// * `Err(String)` - If there is an error writing the input to the cache.
-pub fn add_to_cache(year: &i32, day: &i32, body: &str, context: &Context) -> Result<(), String> {
-    let input_dir = std::env::current_dir().unwrap().join(&context.data_dir);
-    if !input_dir.exists() {
-        fs::create_dir(&input_dir).map_err(|e| e.to_string())?;
+/// * `Err(AocError)` - If there is an error writing the input to the cache.
+pub fn add_to_cache(year: &i32, day: &i32, body: &str, context: &Context) -> Result<(), AocError> {
+    let input_dir = ensure_data_dir(context)?;
+    let input_file = input_dir.join(build_file_name(year, day));
+    let url = format!("{}{}/day/{}/input", context.url, year, day);
+    let metadata = cache::Metadata::new(body, &url, context.profile.as_deref());
+    match &context.key {
+        Some(key) => crypt::write_encrypted(&input_file, body, Some(&metadata), key),
+        None => {
+            cache::write_verified(&input_file, body, &metadata)?;
+            // a stale encrypted copy would otherwise be read in place of the new input
+            let encrypted = crypt::encrypted_path(&input_file);
+            if encrypted.exists() {
+                fs::remove_file(&encrypted).map_err(AocError::cache(&encrypted))?;
+            }
+            Ok(())
+        }
     }
+}
 
-    let input_file = input_dir.join(build_file_name(year, day));
-    fs::write(input_file, body).map_err(|e| e.to_string())
+/// Resolves the data directory of the context against the current directory, creating it if needed.
+/// An absolute data directory, such as the default cache root, is used as is.
+///
+/// Every file in the data directory depends on the account, so a named profile is given its own
+/// partition under `profiles/<name>` while the default profile uses the data directory itself.
+///
+/// # Arguments
+///
+/// * `context` - Defines the context whose data directory is resolved.
+///
+/// # Returns
+///
+/// * `Ok(PathBuf)` - The absolute path of the data directory of the profile.
+/// * `Err(AocError)` - If the profile name is invalid, the current directory is unavailable or the
+///   directory cannot be created.
+pub(crate) fn ensure_data_dir(context: &Context) -> Result<PathBuf, AocError> {
+    let mut dir = data_root(context)?;
+    if let Some(name) = &context.profile {
+        session::check_profile_name(name)?;
+        dir = dir.join("profiles").join(name);
+    }
+    fs::create_dir_all(&dir).map_err(AocError::cache(&dir))?;
+    Ok(dir)
 }
 
 /// Fetches the input from the site for the specified year and day.
 ///
+/// Puzzles that have not unlocked yet are refused without contacting the site, and a body that is
+/// an error page rather than an input is rejected so it never reaches the cache.
+///
 /// # Arguments
 ///
 /// * `year` - A string slice that holds the year of the event.
@@ -114,45 +256,37 @@ pub fn add_to_cache(year: &i32, day: &i32, body: &str, context: &Context) -> Res
 ///
 /// # Returns
 ///
-/// * `Ok(String)` - If the input is fetched successfully.
-/// * `Err(String)` - If there is an error fetching the input.
+/// * `Ok(String)` - The input exactly as the site returned it.
+/// * `Err(AocError)` - If there is an error fetching the input.
 pub async fn get_input_from_site(
     year: &i32,
     day: &i32,
     context: &Context,
-) -> Result<String, String> {
+) -> Result<String, AocError> {
+    unlock::check_unlocked(year, day, &unlock::SystemClock)?;
+
     let path = format!("{}{}/day/{}/input", context.url, year, day);
     println!("Fetching input from {}", path);
 
-    if path.is_empty() {
-        return Err("Path is empty".to_string());
+    let response = http::get(&path, context).await?;
+    let body = error::read_body(response, year, Some(day)).await?;
+    if let Some(reason) = cache::reject_input(&body) {
+        return Err(AocError::MalformedResponse {
+            url: path,
+            reason: reason.to_string(),
+        });
     }
 
-    let jar = reqwest::cookie::Jar::default();
-
-    // the value of domain needs to be extracted from context.url with the schema removed
-    let domain = context.url.host_str().unwrap();
-    jar.add_cookie_str(
-        format!("session={}; Domain={}; Path=/", context.session_id, domain).as_str(),
-        &context.url,
-    );
-
-    let client = Client::builder()
-        .cookie_provider(Arc::new(jar))
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let response = client.get(&path).send().await.map_err(|e| e.to_string())?;
-    let status = &response.status();
-    let body = response.text().await.map_err(|e| e.to_string())?;
-    if !status.is_success() {
-        return Err(format!(
-            "Failed to fetch input from {}.  Response: {}",
-            path, body
-        ));
-    }
+    // kept byte for byte, solutions choose how their input is normalized
+    Ok(body)
+}
 
-    Ok(body.trim().to_string())
+/// Resolves the data directory of the context against the current directory, without the
+/// partition of the profile.
+pub(crate) fn data_root(context: &Context) -> Result<PathBuf, AocError> {
+    Ok(std::env::current_dir()
+        .map_err(AocError::cache(&context.data_dir))?
+        .join(&context.data_dir))
 }
 
 pub fn pretty_print<T: std::fmt::Debug>(matrix: &Vec<Vec<T>>) {
@@ -161,12 +295,27 @@ pub fn pretty_print<T: std::fmt::Debug>(matrix: &Vec<Vec<T>>) {
     }
 }
 
+/// Builds a context for tests that does not throttle or retry requests.
+#[cfg(test)]
+pub(crate) fn test_context(url: &str, data_dir: PathBuf) -> Context {
+    Context::new(
+        "fake_session_id".to_string(),
+        data_dir,
+        Url::parse(url).unwrap(),
+    )
+    .with_http(HttpConfig {
+        retries: 0,
+        min_interval: std::time::Duration::ZERO,
+        ..HttpConfig::default()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use ctor::ctor;
     use std::sync::LazyLock;
-    pub static DATA_DIR: LazyLock<PathBuf> = LazyLock::new(|| get_data_dir());
+    pub static DATA_DIR: LazyLock<PathBuf> = LazyLock::new(get_data_dir);
 
     fn get_data_dir() -> PathBuf {
         std::env::temp_dir().join("aoc_test")
@@ -174,20 +323,36 @@ mod tests {
 
     #[ctor]
     fn init() {
-        // Delete all files from the Data directory
+        // Delete everything from the Data directory, including the nested year and day caches
         if DATA_DIR.exists() {
-            for entry in fs::read_dir(DATA_DIR.clone()).unwrap() {
-                let entry = entry.unwrap();
-                if entry.path().is_file() {
-                    fs::remove_file(entry.path()).unwrap();
-                }
-            }
-        } else {
-            fs::create_dir(DATA_DIR.clone()).unwrap();
+            fs::remove_dir_all(DATA_DIR.clone()).unwrap();
         }
+        fs::create_dir_all(DATA_DIR.clone()).unwrap();
     }
 
     // this is not using tokio::test due to runtime block_on collisions when using mockito
+    #[test]
+    fn site_input_kept_exactly() {
+        let mut server = mockito::Server::new();
+
+        let _m = server
+            .mock("GET", "/2023/day/5/input")
+            .with_status(200)
+            .with_body("  1 2\n 34 5\n")
+            .create();
+
+        let context = test_context(&server.url(), DATA_DIR.clone());
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_input(&2023, &5, &context)).unwrap();
+
+        assert_eq!(result.raw(), "  1 2\n 34 5\n");
+        assert_eq!(
+            get_input_from_cache(&2023, &5, &context).unwrap(),
+            Some("  1 2\n 34 5\n".to_string())
+        );
+    }
+
     #[test]
     fn test_get_input_from_site() {
         let mut server = mockito::Server::new();
@@ -199,11 +364,7 @@ mod tests {
             .with_body("mocked input")
             .create();
 
-        let context = Context {
-            session_id: "fake_session_id".to_string(),
-            data_dir: DATA_DIR.clone(),
-            url: Url::parse(server.url().as_str()).unwrap(),
-        };
+        let context = test_context(&server.url(), DATA_DIR.clone());
 
         let runtime = tokio::runtime::Runtime::new().unwrap();
         let result = runtime.block_on(get_input_from_site(&2023, &1, &context));
@@ -213,7 +374,155 @@ mod tests {
         assert_eq!(result.unwrap(), "mocked input");
     }
 
+    #[test]
+    fn expired_session_unauthorized() {
+        let mut server = mockito::Server::new();
+
+        let _m = server
+            .mock("GET", "/2023/day/1/input")
+            .with_status(400)
+            .with_body("Puzzle inputs differ by user.  Please log in to get your puzzle input.")
+            .create();
+
+        let mut context = test_context(&server.url(), DATA_DIR.clone());
+        context.session_id = "expired_session_id".to_string();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_input_from_site(&2023, &1, &context));
+
+        assert!(matches!(result, Err(AocError::Unauthorized { .. })));
+    }
+
+    #[test]
+    fn locked_puzzle_reported_by_site() {
+        let mut server = mockito::Server::new();
+
+        let _m = server
+            .mock("GET", "/2023/day/25/input")
+            .with_status(404)
+            .with_body("Please don't repeatedly request this endpoint before it unlocks! The calendar countdown is synchronized with the server time; the link will be enabled on the calendar the instant this puzzle becomes available.")
+            .create();
 
+        let context = test_context(&server.url(), DATA_DIR.clone());
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_input_from_site(&2023, &25, &context));
+
+        assert!(matches!(
+            result,
+            Err(AocError::NotUnlocked {
+                year: 2023,
+                day: 25
+            })
+        ));
+    }
+
+    #[test]
+    fn early_request_refused() {
+        let mut server = mockito::Server::new();
+
+        let m = server.mock("GET", "/9999/day/1/input").expect(0).create();
+
+        let context = test_context(&server.url(), DATA_DIR.clone());
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_input_from_site(&9999, &1, &context));
+
+        assert!(matches!(
+            result,
+            Err(AocError::NotUnlocked { year: 9999, day: 1 })
+        ));
+        m.assert();
+    }
+
+    #[test]
+    fn login_page_not_cached() {
+        let mut server = mockito::Server::new();
+
+        let _m = server
+            .mock("GET", "/2023/day/2/input")
+            .with_status(200)
+            .with_body("<!DOCTYPE html>\n<html><a href=\"/auth/login\">[Log In]</a></html>")
+            .create();
+
+        let context = test_context(&server.url(), DATA_DIR.clone());
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_input(&2023, &2, &context));
+
+        assert!(matches!(result, Err(AocError::MalformedResponse { .. })));
+        assert_eq!(get_input_from_cache(&2023, &2, &context).unwrap(), None);
+    }
+
+    #[test]
+    fn offline_reports_missing_input() {
+        let mut server = mockito::Server::new();
+
+        let m = server.mock("GET", "/2023/day/3/input").expect(0).create();
+
+        let context = test_context(&server.url(), DATA_DIR.clone()).with_offline(true);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_input(&2023, &3, &context));
+        assert!(matches!(
+            result,
+            Err(AocError::NotCached {
+                year: 2023,
+                day: 3,
+                ..
+            })
+        ));
+
+        let result = runtime.block_on(get_input_from_site(&2023, &3, &context));
+        assert!(matches!(result, Err(AocError::Offline { .. })));
+        m.assert();
+    }
+
+    #[test]
+    fn encrypted_input_decrypted() {
+        let key = CacheKey::generate();
+        let context =
+            test_context("https://adventofcode.com", DATA_DIR.clone()).with_key(Some(key.clone()));
+        add_to_cache(&1017, &4, "1 2 3", &context).unwrap();
+
+        let file = DATA_DIR.join(build_file_name(&1017, &4));
+        assert!(!file.exists());
+        assert!(crypt::encrypted_path(&file).exists());
+        assert_eq!(
+            get_input_from_cache(&1017, &4, &context).unwrap(),
+            Some("1 2 3".to_string())
+        );
+
+        let plain = test_context("https://adventofcode.com", DATA_DIR.clone());
+        assert!(matches!(
+            get_input_from_cache(&1017, &4, &plain),
+            Err(AocError::Encryption { .. })
+        ));
+    }
+
+    #[test]
+    fn server_error_carries_status() {
+        let mut server = mockito::Server::new();
+
+        let _m = server
+            .mock("GET", "/2023/day/2/input")
+            .with_status(500)
+            .with_body("Internal Server Error")
+            .create();
+
+        let context = test_context(&server.url(), DATA_DIR.clone());
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(get_input_from_site(&2023, &2, &context));
+
+        match result {
+            Err(AocError::Http { status, body, .. }) => {
+                assert_eq!(status, 500);
+                assert_eq!(body, "Internal Server Error");
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
 
     #[test]
     fn cache_hit() {
@@ -221,17 +530,13 @@ mod tests {
         let day = 8;
         let body = "test input";
 
-        let context = Context {
-            session_id: "fake_session_id".to_string(),
-            data_dir: DATA_DIR.clone(),
-            url: Url::parse("https://adventofcode.com").unwrap(),
-        };
+        let context = test_context("https://adventofcode.com", DATA_DIR.clone());
 
         let result = add_to_cache(&year, &day, body, &context);
         assert!(result.is_ok());
 
         let result = get_input_from_cache(&year, &day, &context);
-        assert_eq!(result, Some(body.to_string()));
+        assert_eq!(result.unwrap(), Some(body.to_string()));
     }
 
     #[test]
@@ -239,14 +544,10 @@ mod tests {
         let year = 9999;
         let day = 1;
 
-        let context = Context {
-            session_id: "fake_session_id".to_string(),
-            data_dir: DATA_DIR.clone(),
-            url: Url::parse("https://adventofcode.com").unwrap(),
-        };
+        let context = test_context("https://adventofcode.com", DATA_DIR.clone());
 
         let result = get_input_from_cache(&year, &day, &context);
-        assert_eq!(result, None);
+        assert_eq!(result.unwrap(), None);
     }
 
     #[test]
@@ -255,11 +556,7 @@ mod tests {
         let day = 2;
         let body = "test input";
 
-        let context = Context {
-            session_id: "fake_session_id".to_string(),
-            data_dir: DATA_DIR.clone(),
-            url: Url::parse("https://adventofcode.com").unwrap(),
-        };
+        let context = test_context("https://adventofcode.com", DATA_DIR.clone());
 
         let result = add_to_cache(&year, &day, body, &context);
         assert!(result.is_ok());
@@ -274,11 +571,7 @@ mod tests {
         let year = 1013;
         let day = 7;
 
-        let context = Context {
-            session_id: "fake_session_id".to_string(),
-            data_dir: DATA_DIR.clone(),
-            url: Url::parse("https://adventofcode.com").unwrap(),
-        };
+        let context = test_context("https://adventofcode.com", DATA_DIR.clone());
 
         let result = add_to_cache(&year, &day, "original", &context);
         assert!(result.is_ok());
@@ -291,11 +584,36 @@ mod tests {
         assert_eq!(cached_body, body);
     }
 
+    #[test]
+    fn profiles_partition_cache() {
+        let data_dir = std::env::temp_dir().join("aoc_profile_test");
+        let context = |profile: Option<&str>| {
+            test_context("https://adventofcode.com", data_dir.clone())
+                .with_profile(profile.map(str::to_string))
+        };
+
+        add_to_cache(&2023, &4, "default", &context(None)).unwrap();
+        add_to_cache(&2023, &4, "alice", &context(Some("alice"))).unwrap();
+        add_to_cache(&2023, &4, "bob", &context(Some("bob"))).unwrap();
+
+        let cached = |profile| get_input_from_cache(&2023, &4, &context(profile)).unwrap();
+        assert_eq!(cached(None), Some("default".to_string()));
+        assert_eq!(cached(Some("alice")), Some("alice".to_string()));
+        assert_eq!(cached(Some("bob")), Some("bob".to_string()));
+        assert!(data_dir
+            .join("profiles/alice/2023/day04/input.txt")
+            .exists());
+        assert!(matches!(
+            get_input_from_cache(&2023, &4, &context(Some("../bob"))),
+            Err(AocError::InvalidProfile { .. })
+        ));
+    }
+
     #[test]
     fn test_build_file_name() {
         let year = 1013;
         let day = 7;
         let result = build_file_name(&year, &day);
-        assert_eq!(result, "1013.day7.dat");
+        assert_eq!(result, PathBuf::from("1013/day07/input.txt"));
     }
 }
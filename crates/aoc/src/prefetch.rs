@@ -0,0 +1,218 @@
+use crate::unlock::{self, Clock};
+use crate::{add_to_cache, get_input_from_cache, get_input_from_site, AocError, Context};
+
+/// What happened to one input during a prefetch.
+#[derive(Debug)]
+pub enum Outcome {
+    /// The input was already cached.
+    Cached,
+    /// The input was downloaded into the cache.
+    Fetched,
+    /// The input could not be downloaded.
+    Failed(AocError),
+}
+
+/// The outcome of prefetching the input of one day.
+#[derive(Debug)]
+pub struct Fetch {
+    pub year: i32,
+    pub day: i32,
+    pub outcome: Outcome,
+}
+
+/// Lists the days of an event that have unlocked.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `clock` - The source of the current time.
+///
+/// # Returns
+///
+/// * `Vec<i32>` - The unlocked days in order, empty for a year without an event.
+pub fn unlocked_days(year: &i32, clock: &impl Clock) -> Vec<i32> {
+    (1..=unlock::days_in_event(year))
+        .filter(|day| unlock::check_unlocked(year, day, clock).is_ok())
+        .collect()
+}
+
+/// Lists the unlocked days whose input is not cached, without touching the network.
+///
+/// # Arguments
+///
+/// * `years` - The years of the events to check.
+/// * `context` - Defines the context whose data directory holds the cache.
+/// * `clock` - The source of the current time.
+///
+/// # Returns
+///
+/// * `Ok(Vec<(i32, i32)>)` - The year and day of every missing input.
+/// * `Err(AocError)` - If a cached input cannot be read.
+pub fn missing_inputs(
+    years: &[i32],
+    context: &Context,
+    clock: &impl Clock,
+) -> Result<Vec<(i32, i32)>, AocError> {
+    let mut missing = Vec::new();
+    for year in years {
+        for day in unlocked_days(year, clock) {
+            if get_input_from_cache(year, &day, context)?.is_none() {
+                missing.push((*year, day));
+            }
+        }
+    }
+    Ok(missing)
+}
+
+/// Downloads the input of every unlocked day of the years into the cache, skipping the ones
+/// already cached. Requests are throttled by the context, and a failed day does not stop the
+/// others unless the session is rejected.
+///
+/// # Arguments
+///
+/// * `years` - The years of the events to prefetch.
+/// * `context` - Defines the context to interact with the Advent of Code website.
+/// * `clock` - The source of the current time.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Fetch>)` - The outcome for every unlocked day.
+/// * `Err(AocError)` - If the session is rejected or the cache cannot be read or written.
+pub async fn prefetch(
+    years: &[i32],
+    context: &Context,
+    clock: &impl Clock,
+) -> Result<Vec<Fetch>, AocError> {
+    let mut fetches = Vec::new();
+    for year in years {
+        for day in unlocked_days(year, clock) {
+            let outcome = if get_input_from_cache(year, &day, context)?.is_some() {
+                Outcome::Cached
+            } else {
+                match get_input_from_site(year, &day, context).await {
+                    Ok(body) => {
+                        add_to_cache(year, &day, &body, context)?;
+                        Outcome::Fetched
+                    }
+                    Err(e @ AocError::Unauthorized { .. }) => return Err(e),
+                    Err(e) => Outcome::Failed(e),
+                }
+            };
+            fetches.push(Fetch {
+                year: *year,
+                day,
+                outcome,
+            });
+        }
+    }
+    Ok(fetches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_context;
+    use std::fs;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    struct FixedClock(SystemTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            self.0
+        }
+    }
+
+    // 2025 Day 3 unlocked, Day 4 not yet
+    fn december_third() -> FixedClock {
+        FixedClock(UNIX_EPOCH + Duration::from_secs(1764738000 + 60))
+    }
+
+    fn build_context(url: &str, name: &str) -> Context {
+        let data_dir = std::env::temp_dir().join("aoc_prefetch_test").join(name);
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir).unwrap();
+        }
+        test_context(url, data_dir)
+    }
+
+    #[test]
+    fn only_unlocked_days_listed() {
+        assert_eq!(unlocked_days(&2025, &december_third()), vec![1, 2, 3]);
+        assert_eq!(unlocked_days(&2015, &december_third()).len(), 25);
+        assert!(unlocked_days(&2014, &december_third()).is_empty());
+    }
+
+    #[test]
+    fn missing_inputs_listed() {
+        let context =
+            build_context("https://adventofcode.com", "missing_inputs_listed").with_offline(true);
+        add_to_cache(&2025, &2, "cached", &context).unwrap();
+
+        let result = missing_inputs(&[2025], &context, &december_third()).unwrap();
+        assert_eq!(result, vec![(2025, 1), (2025, 3)]);
+    }
+
+    // this is not using tokio::test due to runtime block_on collisions when using mockito
+    #[test]
+    fn uncached_days_fetched() {
+        let mut server = mockito::Server::new();
+        let fetched = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/2025/day/[13]/input$".to_string()),
+            )
+            .with_status(200)
+            .with_body("1 2 3\n")
+            .expect(2)
+            .create();
+
+        let context = build_context(&server.url(), "uncached_days_fetched");
+        add_to_cache(&2025, &2, "cached", &context).unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime
+            .block_on(prefetch(&[2025], &context, &december_third()))
+            .unwrap();
+
+        let outcomes = result
+            .iter()
+            .map(|f| (f.day, matches!(f.outcome, Outcome::Fetched)))
+            .collect::<Vec<_>>();
+        assert_eq!(outcomes, vec![(1, true), (2, false), (3, true)]);
+        assert!(missing_inputs(&[2025], &context, &december_third())
+            .unwrap()
+            .is_empty());
+        fetched.assert();
+    }
+
+    #[test]
+    fn failed_day_does_not_stop_prefetch() {
+        let mut server = mockito::Server::new();
+        let _failed = server
+            .mock("GET", "/2025/day/1/input")
+            .with_status(500)
+            .create();
+        let _fetched = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/2025/day/[23]/input$".to_string()),
+            )
+            .with_status(200)
+            .with_body("1 2 3\n")
+            .create();
+
+        let context = build_context(&server.url(), "failed_day_does_not_stop_prefetch");
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime
+            .block_on(prefetch(&[2025], &context, &december_third()))
+            .unwrap();
+
+        assert!(matches!(
+            result[0].outcome,
+            Outcome::Failed(AocError::Http { .. })
+        ));
+        assert!(matches!(result[2].outcome, Outcome::Fetched));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::ledger::Ledger;
+use crate::{cache, ensure_data_dir, error, html, http, unlock, AocError, Context, Verdict};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fetches the puzzle description for the specified year and day as Markdown.
+///
+/// The cached description is used when present, unless it only holds part one and the ledger shows
+/// part one has since been solved, in which case the page is fetched again to pick up part two.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The Markdown rendering of every unlocked part of the puzzle.
+/// * `Err(AocError)` - If there is an error fetching or caching the description.
+pub async fn get_description(year: &i32, day: &i32, context: &Context) -> Result<String, AocError> {
+    println!("Fetching description for AOC {} Day {}", year, day);
+
+    if let Some(markdown) = get_description_from_cache(year, day, context)? {
+        if !part_two_unlocked_since(&markdown, year, day, context) {
+            return Ok(markdown);
+        }
+        println!("Part two unlocked since the description was cached");
+    }
+
+    let page = get_page_from_site(year, day, context).await?;
+    let articles = parse_articles(&page).ok_or_else(|| AocError::MalformedResponse {
+        url: format!("{}{}/day/{}", context.url, year, day),
+        reason: "Puzzle page did not contain a description".to_string(),
+    })?;
+    add_to_cache(year, day, &articles, context)?;
+
+    Ok(render_markdown(&articles))
+}
+
+/// Retrieves the Markdown description from the cache if it exists.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `context` - Defines the context whose data directory holds the cache.
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - If the description has been cached.
+/// * `Ok(None)` - If the description has not been cached.
+/// * `Err(AocError)` - If the cached description cannot be read.
+pub fn get_description_from_cache(
+    year: &i32,
+    day: &i32,
+    context: &Context,
+) -> Result<Option<String>, AocError> {
+    read_cached(
+        &build_file_name(year, day, cache::DESCRIPTION_FILE),
+        context,
+    )
+}
+
+/// Retrieves the cached puzzle markup, holding one `<article>` element per unlocked part.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `context` - Defines the context whose data directory holds the cache.
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - If the puzzle page has been cached.
+/// * `Ok(None)` - If the puzzle page has not been cached.
+/// * `Err(AocError)` - If the cached page cannot be read.
+pub fn get_page_from_cache(
+    year: &i32,
+    day: &i32,
+    context: &Context,
+) -> Result<Option<String>, AocError> {
+    read_cached(&build_file_name(year, day, cache::PAGE_FILE), context)
+}
+
+/// Fetches the puzzle page from the site for the specified year and day.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The full HTML of the puzzle page.
+/// * `Err(AocError)` - If there is an error fetching the page.
+pub async fn get_page_from_site(
+    year: &i32,
+    day: &i32,
+    context: &Context,
+) -> Result<String, AocError> {
+    unlock::check_unlocked(year, day, &unlock::SystemClock)?;
+
+    let path = format!("{}{}/day/{}", context.url, year, day);
+    println!("Fetching puzzle from {}", path);
+
+    let response = http::get(&path, context).await?;
+    error::read_body(response, year, Some(day)).await
+}
+
+/// Extracts the description articles from a puzzle page.
+///
+/// # Arguments
+///
+/// * `page` - A string slice that holds the full HTML of the puzzle page.
+///
+/// # Returns
+///
+/// * `Some(String)` - One `<article>` element per unlocked part.
+/// * `None` - If the page does not contain a puzzle description.
+pub fn parse_articles(page: &str) -> Option<String> {
+    let articles = html::articles(page);
+    if articles.is_empty() {
+        return None;
+    }
+
+    Some(
+        articles
+            .iter()
+            .map(|article| format!("<article>{}</article>\n", article))
+            .collect(),
+    )
+}
+
+/// Renders the description articles of a puzzle page as Markdown.
+///
+/// # Arguments
+///
+/// * `page` - A string slice that holds puzzle markup containing one or more `<article>` elements.
+///
+/// # Returns
+///
+/// * `String` - The Markdown rendering of each part, separated by a blank line.
+pub fn render_markdown(page: &str) -> String {
+    html::articles(page)
+        .iter()
+        .map(|article| html::to_markdown(article))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Keeps a copy of the description next to the solution of its day, as `puzzle.md` in the day
+/// crate, so it can be read offline and is committed along with the solution.
+///
+/// # Arguments
+///
+/// * `crate_dir` - The root of the day crate, such as `crates/day7`.
+/// * `markdown` - The Markdown rendering of the description.
+///
+/// # Returns
+///
+/// * `Ok(Some(PathBuf))` - The description file, if it was written.
+/// * `Ok(None)` - If there is no day crate yet or its copy is already up to date.
+/// * `Err(AocError)` - If the description file cannot be read or written.
+pub fn save_description(crate_dir: &Path, markdown: &str) -> Result<Option<PathBuf>, AocError> {
+    if !crate_dir.is_dir() {
+        return Ok(None);
+    }
+    let file = crate_dir.join(cache::DESCRIPTION_FILE);
+    if file.exists() && fs::read_to_string(&file).map_err(AocError::io(&file))? == markdown {
+        return Ok(None);
+    }
+    fs::write(&file, markdown).map_err(AocError::io(&file))?;
+    Ok(Some(file))
+}
+
+fn part_two_unlocked_since(markdown: &str, year: &i32, day: &i32, context: &Context) -> bool {
+    if markdown.contains("--- Part Two ---") {
+        return false;
+    }
+
+    match Ledger::load(year, day, context) {
+        Ok(ledger) => ledger.guesses(&1).any(|g| g.verdict == Verdict::Correct),
+        Err(_) => false,
+    }
+}
+
+fn add_to_cache(year: &i32, day: &i32, articles: &str, context: &Context) -> Result<(), AocError> {
+    let dir = ensure_data_dir(context)?;
+    let page_file = dir.join(build_file_name(year, day, cache::PAGE_FILE));
+    cache::write_atomic(&page_file, articles)?;
+    let markdown_file = dir.join(build_file_name(year, day, cache::DESCRIPTION_FILE));
+    cache::write_atomic(&markdown_file, render_markdown(articles))
+}
+
+fn read_cached(file_name: &Path, context: &Context) -> Result<Option<String>, AocError> {
+    let file = ensure_data_dir(context)?.join(file_name);
+    if !file.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(&file)
+        .map(Some)
+        .map_err(AocError::cache(&file))
+}
+
+/// Builds the path of a cached description file relative to the data directory.
+fn build_file_name(year: &i32, day: &i32, file: &str) -> PathBuf {
+    cache::day_dir(year, day).join(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_context;
+
+    fn build_context(url: &str, name: &str) -> Context {
+        let data_dir = std::env::temp_dir().join("aoc_puzzle_test").join(name);
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir).unwrap();
+        }
+        test_context(url, data_dir)
+    }
+
+    #[test]
+    fn complete_page_converted() {
+        let articles = parse_articles(include_str!("fixtures/day_complete.html")).unwrap();
+        let result = render_markdown(&articles);
+        assert_eq!(result, include_str!("fixtures/day_complete.md"));
+    }
+
+    #[test]
+    fn part_one_page_converted() {
+        let articles = parse_articles(include_str!("fixtures/day_part1.html")).unwrap();
+        let result = render_markdown(&articles);
+        assert!(result.starts_with("## --- Day 1: Secret Entrance ---\n\n"));
+        assert!(!result.contains("--- Part Two ---"));
+    }
+
+    #[test]
+    fn page_without_article_rejected() {
+        let result = parse_articles("<html><body><p>404 Not Found</p></body></html>");
+        assert_eq!(result, None);
+    }
+
+    // this is not using tokio::test due to runtime block_on collisions when using mockito
+    #[test]
+    fn test_get_description() {
+        let mut server = mockito::Server::new();
+
+        let page = server
+            .mock("GET", "/2023/day/1")
+            .with_status(200)
+            .with_header("content-type", "text/html")
+            .with_body(include_str!("fixtures/day_complete.html"))
+            .expect(1)
+            .create();
+
+        let context = build_context(server.url().as_str(), "test_get_description");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let fetched = runtime
+            .block_on(get_description(&2023, &1, &context))
+            .unwrap();
+        assert_eq!(fetched, include_str!("fixtures/day_complete.md"));
+
+        let cached = runtime
+            .block_on(get_description(&2023, &1, &context))
+            .unwrap();
+        assert_eq!(cached, fetched);
+        assert!(get_page_from_cache(&2023, &1, &context).unwrap().is_some());
+        page.assert();
+    }
+
+    #[test]
+    fn description_saved_in_day_crate() {
+        let crate_dir = std::env::temp_dir()
+            .join("aoc_puzzle_test")
+            .join("description_saved_in_day_crate");
+        if crate_dir.exists() {
+            fs::remove_dir_all(&crate_dir).unwrap();
+        }
+
+        assert_eq!(save_description(&crate_dir, "# Day 1").unwrap(), None);
+        fs::create_dir_all(&crate_dir).unwrap();
+        assert_eq!(
+            save_description(&crate_dir, "# Day 1").unwrap(),
+            Some(crate_dir.join("puzzle.md"))
+        );
+        assert_eq!(save_description(&crate_dir, "# Day 1").unwrap(), None);
+        assert_eq!(
+            fs::read_to_string(crate_dir.join("puzzle.md")).unwrap(),
+            "# Day 1"
+        );
+    }
+
+    #[test]
+    fn part_two_refetched_after_solve() {
+        let mut server = mockito::Server::new();
+
+        let page = server
+            .mock("GET", "/2023/day/1")
+            .with_status(200)
+            .with_body(include_str!("fixtures/day_complete.html"))
+            .expect(1)
+            .create();
+
+        let context = build_context(server.url().as_str(), "part_two_refetched_after_solve");
+        let part_one = parse_articles(include_str!("fixtures/day_part1.html")).unwrap();
+        add_to_cache(&2023, &1, &part_one, &context).unwrap();
+        let mut ledger = Ledger::load(&2023, &1, &context).unwrap();
+        ledger.record(&1, "1097", &Verdict::Correct).unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime
+            .block_on(get_description(&2023, &1, &context))
+            .unwrap();
+
+        assert!(result.contains("--- Part Two ---"));
+        page.assert();
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{html, AocError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The worked example from a puzzle description and the answers it produces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub input: String,
+    pub answers: Vec<(i32, String)>,
+}
+
+/// Extracts the example input and the example answer of each part from a puzzle page.
+///
+/// The example input is the first preformatted block introduced by a paragraph mentioning an
+/// example, falling back to the largest block. The example answer of a part is the last
+/// emphasised code span in that part.
+///
+/// # Arguments
+///
+/// * `page` - A string slice that holds the puzzle markup, as returned by `puzzle::get_page_from_cache`.
+///
+/// # Returns
+///
+/// * `Some(Sample)` - If an example input was found.
+/// * `None` - If the page has no description or no preformatted block.
+pub fn extract_sample(page: &str) -> Option<Sample> {
+    let articles = html::articles(page);
+    let blocks = html::preformatted(articles.first()?);
+    let block = blocks
+        .iter()
+        .find(|b| b.lead.to_lowercase().contains("example"))
+        .or_else(|| blocks.iter().max_by_key(|b| b.text.len()))?;
+
+    let input = block
+        .text
+        .strip_suffix('\n')
+        .unwrap_or(&block.text)
+        .to_string();
+
+    let answers = articles
+        .iter()
+        .enumerate()
+        .filter_map(|(i, article)| {
+            html::emphasised_code(article)
+                .pop()
+                .map(|answer| (i as i32 + 1, answer))
+        })
+        .collect();
+
+    Some(Sample { input, answers })
+}
+
+/// Writes `sample.dat` and `sample.answers` into a directory.
+///
+/// # Arguments
+///
+/// * `dir` - The directory to write the files into, normally the `src` folder of a day crate.
+/// * `sample` - The sample to write.
+///
+/// # Returns
+///
+/// * `Ok(Vec<PathBuf>)` - The files that were written.
+/// * `Err(AocError)` - If there is an error writing either file.
+pub fn write_sample(dir: &Path, sample: &Sample) -> Result<Vec<PathBuf>, AocError> {
+    fs::create_dir_all(dir).map_err(AocError::io(dir))?;
+
+    let input_file = dir.join("sample.dat");
+    fs::write(&input_file, &sample.input).map_err(AocError::io(&input_file))?;
+
+    let answers_file = dir.join("sample.answers");
+    let answers = sample
+        .answers
+        .iter()
+        .map(|(part, answer)| format!("{}\t{}\n", part, answer))
+        .collect::<String>();
+    fs::write(&answers_file, answers).map_err(AocError::io(&answers_file))?;
+
+    Ok(vec![input_file, answers_file])
+}
+
+/// Scaffolds a day crate around a sample.
+///
+/// The sample files are always refreshed. `Cargo.toml` and `src/lib.rs` are only created when
+/// missing, so an existing solution is never overwritten.
+///
+/// # Arguments
+///
+/// * `crate_dir` - The root of the day crate, such as `crates/day7`.
+/// * `year` - The year of the event, used to register the solution.
+/// * `day` - The day of the event.
+/// * `sample` - The sample extracted from the puzzle description.
+///
+/// # Returns
+///
+/// * `Ok(Vec<PathBuf>)` - The files that were written.
+/// * `Err(AocError)` - If there is an error writing any of the files.
+pub fn scaffold_day(
+    crate_dir: &Path,
+    year: &i32,
+    day: &i32,
+    sample: &Sample,
+) -> Result<Vec<PathBuf>, AocError> {
+    let src = crate_dir.join("src");
+    let mut written = write_sample(&src, sample)?;
+
+    let manifest = crate_dir.join("Cargo.toml");
+    if !manifest.exists() {
+        fs::write(&manifest, build_manifest(day)).map_err(AocError::io(&manifest))?;
+        written.push(manifest);
+    }
+
+    let lib = src.join("lib.rs");
+    if !lib.exists() {
+        fs::write(&lib, build_lib(year, day, sample)).map_err(AocError::io(&lib))?;
+        written.push(lib);
+    }
+
+    Ok(written)
+}
+
+fn build_manifest(day: &i32) -> String {
+    format!(
+        "[package]\nname = \"day{}\"\nversion = \"0.1.0\"\nedition = \"2024\"\n\n[dependencies]\naoc = {{ path = \"../aoc\" }}\n",
+        day
+    )
+}
+
+fn build_lib(year: &i32, day: &i32, sample: &Sample) -> String {
+    let tests = sample
+        .answers
+        .iter()
+        .map(|(part, answer)| {
+            let expected = match answer.parse::<i64>() {
+                Ok(value) => format!("assert_eq!(result, {});", value),
+                Err(_) => format!("assert_eq!(result, {:?});", answer),
+            };
+            format!(
+                "
+    #[test]
+    fn sample_part{part}_valid() {{
+        let sample = get_sample();
+        let result = Day{day}::part{part}(&Day{day}::parse(&sample).unwrap());
+        {expected}
+    }}
+"
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        "use aoc::{{Answer, Input, Normalization, ParseError, Solution}};
+
+pub struct Day{day};
+
+aoc::register_solution!({year}, {day}, Day{day});
+
+impl Solution for Day{day} {{
+    type Parsed = Vec<String>;
+    type Part1 = Answer;
+    type Part2 = Answer;
+
+    const NORMALIZATION: Normalization = Normalization::TrimTrailingNewline;
+
+    fn parse(input: &Input) -> Result<Vec<String>, ParseError> {{
+        Ok(input.lines().iter().map(|line| line.to_string()).collect())
+    }}
+
+    fn part1(_parsed: &Vec<String>) -> Answer {{
+        Answer::Unsolved
+    }}
+
+    fn part2(_parsed: &Vec<String>) -> Answer {{
+        Answer::Unsolved
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    fn get_sample() -> Input {{
+        Input::from(include_str!(\"sample.dat\")).normalize(Day{day}::NORMALIZATION)
+    }}
+{tests}}}
+"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_page_sample() {
+        let result = extract_sample(include_str!("fixtures/day_complete.html")).unwrap();
+        assert_eq!(
+            result.input,
+            "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82"
+        );
+        assert_eq!(
+            result.answers,
+            vec![(1, "3".to_string()), (2, "6".to_string())]
+        );
+    }
+
+    #[test]
+    fn part_one_page_sample() {
+        let result = extract_sample(include_str!("fixtures/day_part1.html")).unwrap();
+        assert_eq!(result.answers, vec![(1, "3".to_string())]);
+    }
+
+    #[test]
+    fn largest_block_without_example_lead() {
+        let page = "<article><p>Consider:</p><pre><code>ab\n</code></pre><p>Or:</p><pre><code>abc\ndef\n</code></pre><p>Gives <code><em>7</em></code>.</p></article>";
+        let result = extract_sample(page).unwrap();
+        assert_eq!(result.input, "abc\ndef");
+        assert_eq!(result.answers, vec![(1, "7".to_string())]);
+    }
+
+    #[test]
+    fn page_without_example_rejected() {
+        let result = extract_sample("<article><p>No examples today</p></article>");
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn scaffold_keeps_existing_solution() {
+        let dir = std::env::temp_dir().join("aoc_sample_test").join("day99");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        let sample = Sample {
+            input: "1\n2".to_string(),
+            answers: vec![(1, "3".to_string()), (2, "abc".to_string())],
+        };
+
+        let written = scaffold_day(&dir, &2025, &99, &sample).unwrap();
+        assert_eq!(written.len(), 4);
+        let lib = fs::read_to_string(dir.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("assert_eq!(result, 3);"));
+        assert!(lib.contains("aoc::register_solution!(2025, 99, Day99);"));
+        assert!(lib.contains("assert_eq!(result, \"abc\");"));
+        assert!(fs::read_to_string(dir.join("Cargo.toml"))
+            .unwrap()
+            .contains("aoc = { path = \"../aoc\" }"));
+        assert_eq!(
+            fs::read_to_string(dir.join("src/sample.answers")).unwrap(),
+            "1\t3\n2\tabc\n"
+        );
+
+        fs::write(dir.join("src/lib.rs"), "solved").unwrap();
+        let written = scaffold_day(&dir, &2025, &99, &sample).unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!(
+            fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+            "solved"
+        );
+    }
+}
@@ -1,60 +1,414 @@
-    use std::fs;
-    use std::path::PathBuf;
-
-    /// Retrieves the session ID from the `.session` file.
-    ///
-    /// # Returns
-    ///
-    /// * `Ok(String)` - If the session ID is read successfully.
-    /// * `Err(String)` - If there is an error reading the session ID.
-    pub fn get_session_id(root: &PathBuf) -> Option<String> {
-        // check if .session file exists
-        let session_path = root.join(".session");
-        return if fs::metadata(&session_path).is_ok() {
-            Some(fs::read_to_string(&session_path).unwrap())
-        } else {
-            None
-        }
+use crate::{http, AocError, Context};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The environment variable checked before any session file.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// Checks a profile name is safe to use in file names and environment variable names.
+///
+/// # Arguments
+///
+/// * `name` - The name of the profile.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the name only holds letters, digits, `-` and `_`.
+/// * `Err(AocError)` - If the name is empty or holds any other character.
+pub fn check_profile_name(name: &str) -> Result<(), AocError> {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(AocError::InvalidProfile {
+            name: name.to_string(),
+        })
+    }
+}
+
+/// The environment variable holding the session of a profile, `AOC_SESSION` for the default
+/// profile and `AOC_SESSION_<NAME>` for a named one.
+pub fn session_env(profile: Option<&str>) -> String {
+    match profile {
+        None => SESSION_ENV.to_string(),
+        Some(name) => format!("{}_{}", SESSION_ENV, name.to_uppercase().replace('-', "_")),
     }
+}
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+/// The name of the session file of a profile, `.session` for the default profile and
+/// `.session.<name>` for a named one.
+pub fn session_file_name(profile: Option<&str>) -> String {
+    match profile {
+        None => ".session".to_string(),
+        Some(name) => format!(".session.{}", name),
+    }
+}
 
-        use std::{path::PathBuf, sync::LazyLock};
-        pub static DATA_DIR: LazyLock<PathBuf> = LazyLock::new(|| get_data_dir());
+/// Where a session ID was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionSource {
+    Environment(String),
+    File(PathBuf),
+}
 
-        fn get_data_dir() -> PathBuf {
-            std::env::temp_dir().join("aoc_session_test")
+impl fmt::Display for SessionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionSource::Environment(name) => write!(f, "the {} environment variable", name),
+            SessionSource::File(path) => write!(f, "{}", path.display()),
         }
+    }
+}
+
+/// A session ID along with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub id: String,
+    pub source: SessionSource,
+}
 
-        #[test]
-        fn test_get_session_id() {
-            create_session_file();
-            let session_id = get_session_id(&DATA_DIR.clone());
-            assert_eq!(session_id, Some("fake_session_id".to_string()));
+/// Retrieves the session ID of the default profile, see `find_session` for the places that are
+/// searched.
+///
+/// # Arguments
+///
+/// * `root` - The directory to start searching for a `.session` file from.
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - If a well formed session ID is found.
+/// * `Ok(None)` - If no session ID is configured anywhere.
+/// * `Err(AocError)` - If a session file cannot be read or holds a malformed session ID.
+pub fn get_session_id(root: &Path) -> Result<Option<String>, AocError> {
+    Ok(find_session(root, None)?.map(|session| session.id))
+}
+
+/// Finds the session ID of a profile, checking in order the `AOC_SESSION` environment variable,
+/// a `.session` file in `root` or any of its parents, and the `session` file in the user
+/// config directory. A named profile uses `AOC_SESSION_<NAME>`, `.session.<name>` and
+/// `session.<name>` instead.
+///
+/// # Arguments
+///
+/// * `root` - The directory to start searching for a `.session` file from.
+/// * `profile` - The name of the profile, or `None` for the default profile.
+///
+/// # Returns
+///
+/// * `Ok(Some(Session))` - The first session ID found.
+/// * `Ok(None)` - If no session ID is configured anywhere.
+/// * `Err(AocError)` - If the profile name is invalid, a session file cannot be read or it
+///   holds a malformed session ID.
+pub fn find_session(root: &Path, profile: Option<&str>) -> Result<Option<Session>, AocError> {
+    if let Some(name) = profile {
+        check_profile_name(name)?;
+    }
+    let env = session_env(profile);
+    resolve_session(
+        std::env::var(&env).ok().map(|value| (env, value)),
+        root,
+        &session_file_name(profile),
+        config_file(profile).as_deref(),
+    )
+}
+
+/// The session file of a profile in the user config directory, such as
+/// `~/.config/aoc/session` on Linux.
+pub fn config_file(profile: Option<&str>) -> Option<PathBuf> {
+    let file_name = session_file_name(profile);
+    dirs::config_dir().map(|dir| dir.join("aoc").join(&file_name[1..]))
+}
+
+/// Resolves the session ID from explicit sources, in the order used by `find_session`.
+///
+/// # Arguments
+///
+/// * `env` - The name and value of the session environment variable, if it is set.
+/// * `root` - The directory to start searching for a session file from.
+/// * `file_name` - The name of the session file searched for in `root` and its parents.
+/// * `config_file` - The session file in the user config directory, if there is one.
+///
+/// # Returns
+///
+/// * `Ok(Some(Session))` - The first session ID found.
+/// * `Ok(None)` - If none of the sources hold a session ID.
+/// * `Err(AocError)` - If a session file cannot be read or holds a malformed session ID.
+pub fn resolve_session(
+    env: Option<(String, String)>,
+    root: &Path,
+    file_name: &str,
+    config_file: Option<&Path>,
+) -> Result<Option<Session>, AocError> {
+    if let Some((name, value)) = env.filter(|(_, value)| !value.trim().is_empty()) {
+        return parse_session_id(&value, SessionSource::Environment(name)).map(Some);
+    }
+
+    let candidates = root
+        .ancestors()
+        .map(|dir| dir.join(file_name))
+        .chain(config_file.map(Path::to_path_buf));
+    for session_path in candidates {
+        if session_path.is_file() {
+            let value = fs::read_to_string(&session_path).map_err(AocError::io(&session_path))?;
+            return parse_session_id(&value, SessionSource::File(session_path)).map(Some);
         }
+    }
 
-        #[test]
-        fn when_no_session_file_exists() {
-            clear_session_file();
+    Ok(None)
+}
 
-            let session_id = get_session_id(&DATA_DIR.clone());
-            assert_eq!(session_id, None);
+/// Trims and checks a session ID, which is the hexadecimal value of the `session` cookie.
+/// A leading `session=` copied along with the cookie value is dropped.
+///
+/// # Arguments
+///
+/// * `value` - The raw session ID as read from its source.
+/// * `source` - Where the session ID was read from.
+///
+/// # Returns
+///
+/// * `Ok(Session)` - The trimmed session ID.
+/// * `Err(AocError)` - If the value is not a plausible session ID.
+pub fn parse_session_id(value: &str, source: SessionSource) -> Result<Session, AocError> {
+    let id = value.trim();
+    let id = id.strip_prefix("session=").unwrap_or(id);
+
+    let reason = if id.is_empty() {
+        Some("it is empty")
+    } else if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some("it should only contain hexadecimal digits")
+    } else if id.len() < 32 {
+        Some("it is too short")
+    } else {
+        None
+    };
+
+    match reason {
+        Some(reason) => Err(AocError::InvalidSession {
+            origin: source.to_string(),
+            reason: reason.to_string(),
+        }),
+        None => Ok(Session {
+            id: id.to_string(),
+            source,
+        }),
+    }
+}
+
+/// Checks with the site that the session in the context is still signed in, so an expired
+/// cookie is reported before it is used to fetch anything.
+///
+/// # Arguments
+///
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the site recognises the session.
+/// * `Err(AocError)` - `Unauthorized` if the session has expired, or the failure reaching the site.
+pub async fn validate_session(context: &Context) -> Result<(), AocError> {
+    let path = format!("{}settings", context.url);
+    println!("Checking session with {}", path);
+
+    let response = http::get(&path, context).await?;
+    let status = response.status();
+    let body = response.text().await.map_err(AocError::network(&path))?;
+
+    // a signed out visitor is redirected to a page offering to log in
+    if matches!(status.as_u16(), 400 | 401 | 403) || body.contains("/auth/login") {
+        Err(AocError::Unauthorized { url: path })
+    } else if status.is_success() {
+        Ok(())
+    } else {
+        Err(AocError::Http {
+            url: path,
+            status: status.as_u16(),
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_context;
+
+    const SESSION_ID: &str = "53616c7465645f5f0123456789abcdef0123456789abcdef";
+
+    fn get_data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("aoc_session_test").join(name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
         }
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn build_context(url: &str) -> Context {
+        let mut context = test_context(url, std::env::temp_dir().join("aoc_session_test"));
+        context.session_id = SESSION_ID.to_string();
+        context
+    }
+
+    #[test]
+    fn test_get_session_id() {
+        let dir = get_data_dir("test_get_session_id");
+        create_session_file(&dir, &format!("{}\n", SESSION_ID));
 
-        fn clear_session_file() {
-            
-            let session_path = DATA_DIR.join(".session");
-            if fs::metadata(&session_path).is_ok() {
-                fs::remove_file(&session_path).unwrap();
-            }
+        let session = resolve_session(None, &dir, ".session", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.id, SESSION_ID);
+        assert_eq!(session.source, SessionSource::File(dir.join(".session")));
+    }
+
+    #[test]
+    fn when_no_session_file_exists() {
+        let dir = get_data_dir("when_no_session_file_exists");
+
+        let session = resolve_session(None, &dir, ".session", Some(&dir.join("missing"))).unwrap();
+        assert_eq!(session, None);
+    }
+
+    #[test]
+    fn environment_checked_first() {
+        let dir = get_data_dir("environment_checked_first");
+        create_session_file(&dir, "0123456789abcdef0123456789abcdef");
+
+        let session = resolve_session(
+            Some((SESSION_ENV.to_string(), format!(" {} ", SESSION_ID))),
+            &dir,
+            ".session",
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(session.id, SESSION_ID);
+        assert_eq!(
+            session.source,
+            SessionSource::Environment(SESSION_ENV.to_string())
+        );
+
+        let session = resolve_session(
+            Some((SESSION_ENV.to_string(), " ".to_string())),
+            &dir,
+            ".session",
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(session.source, SessionSource::File(dir.join(".session")));
+    }
+
+    #[test]
+    fn parent_directories_searched() {
+        let dir = get_data_dir("parent_directories_searched");
+        let nested = dir.join("crates").join("day1");
+        fs::create_dir_all(&nested).unwrap();
+        create_session_file(&dir, SESSION_ID);
+
+        let session = resolve_session(None, &nested, ".session", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.source, SessionSource::File(dir.join(".session")));
+    }
+
+    #[test]
+    fn config_file_used_last() {
+        let dir = get_data_dir("config_file_used_last");
+        let config = dir.join("config");
+        fs::write(&config, SESSION_ID).unwrap();
+
+        let session = resolve_session(None, &dir, ".session", Some(&config))
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.source, SessionSource::File(config));
+    }
+
+    #[test]
+    fn profiles_use_their_own_files() {
+        let dir = get_data_dir("profiles_use_their_own_files");
+        create_session_file(&dir, SESSION_ID);
+        fs::write(
+            dir.join(".session.alice"),
+            "0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+
+        let file_name = session_file_name(Some("alice"));
+        let session = resolve_session(None, &dir, &file_name, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.id, "0123456789abcdef0123456789abcdef");
+        assert_eq!(session_env(Some("team-b")), "AOC_SESSION_TEAM_B");
+        assert_eq!(session_env(None), "AOC_SESSION");
+    }
+
+    #[test]
+    fn profile_names_checked() {
+        assert!(check_profile_name("alice_2").is_ok());
+        for name in ["", "../alice", "a b"] {
+            assert!(matches!(
+                check_profile_name(name),
+                Err(AocError::InvalidProfile { .. })
+            ));
         }
+    }
 
-        fn create_session_file() {
-            let session_path = DATA_DIR.join(".session");
-            println!("Creating test file at {:?}", session_path);
-            fs::write(&session_path, "fake_session_id").unwrap();
+    #[test]
+    fn session_format_checked() {
+        let source = || SessionSource::Environment(SESSION_ENV.to_string());
+        let session = parse_session_id(&format!("session={}\r\n", SESSION_ID), source());
+        assert_eq!(session.unwrap().id, SESSION_ID);
+
+        for value in ["", "fake_session_id", "abc123"] {
+            assert!(matches!(
+                parse_session_id(value, source()),
+                Err(AocError::InvalidSession { .. })
+            ));
         }
+    }
+
+    // this is not using tokio::test due to runtime block_on collisions when using mockito
+    #[test]
+    fn signed_in_session_accepted() {
+        let mut server = mockito::Server::new();
+        let m = server
+            .mock("GET", "/settings")
+            .with_status(200)
+            .with_body("<div class=\"user\">someone</div>")
+            .create();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(validate_session(&build_context(&server.url())));
+
+        assert!(result.is_ok());
+        m.assert();
+    }
+
+    #[test]
+    fn expired_session_detected() {
+        let mut server = mockito::Server::new();
+        let m = server
+            .mock("GET", "/settings")
+            .with_status(200)
+            .with_body("<a href=\"/auth/login\">[Log In]</a>")
+            .create();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(validate_session(&build_context(&server.url())));
+
+        assert!(matches!(result, Err(AocError::Unauthorized { .. })));
+        m.assert();
+    }
 
+    fn create_session_file(dir: &Path, session_id: &str) {
+        let session_path = dir.join(".session");
+        println!("Creating test file at {:?}", session_path);
+        fs::write(&session_path, session_id).unwrap();
     }
+}
@@ -0,0 +1,294 @@
+use crate::bench::{self, Report, Settings, Stats};
+use crate::{Answer, Input, Normalization, ParseError};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A solution to the puzzle of one day. The input is parsed once and the parsed form is shared by
+/// both parts.
+pub trait Solution {
+    /// The input once parsed.
+    type Parsed;
+
+    /// The answer to the first part, anything that converts into an `Answer` such as `i64`.
+    type Part1: Into<Answer>;
+
+    /// The answer to the second part, anything that converts into an `Answer` such as `i64`.
+    type Part2: Into<Answer>;
+
+    /// How the input is normalized before it is parsed.
+    const NORMALIZATION: Normalization = Normalization::TrimTrailingNewline;
+
+    /// Parses the normalized input, reporting where it does not look like this day's input.
+    fn parse(input: &Input) -> Result<Self::Parsed, ParseError>;
+
+    /// Solves the first part of the puzzle.
+    fn part1(parsed: &Self::Parsed) -> Self::Part1;
+
+    /// Solves the second part of the puzzle.
+    fn part2(parsed: &Self::Parsed) -> Self::Part2;
+}
+
+/// The answers to both parts of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Solved {
+    pub part1: Answer,
+    pub part2: Answer,
+    pub timings: Timings,
+}
+
+/// The time spent in each phase of solving a puzzle. Parsing includes normalizing the input and
+/// happens once, before both parts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+}
+
+impl Timings {
+    /// The time spent in every phase.
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1 + self.part2
+    }
+}
+
+/// A solution registered with `register_solution!`.
+#[derive(Debug, Clone, Copy)]
+pub struct Registration {
+    pub year: i32,
+    pub day: i32,
+    /// The name of the type implementing the solution.
+    pub name: &'static str,
+    pub normalization: Normalization,
+    solve: fn(Input) -> Result<Solved, ParseError>,
+    bench: fn(Input, &Settings) -> Result<[Stats; 3], ParseError>,
+}
+
+impl Registration {
+    /// Describes a solution, used by `register_solution!`.
+    pub fn new<S: Solution>(year: i32, day: i32, name: &'static str) -> Registration {
+        Registration {
+            year,
+            day,
+            name,
+            normalization: S::NORMALIZATION,
+            solve: solve::<S>,
+            bench: bench::run::<S>,
+        }
+    }
+
+    /// Normalizes and parses the input, then solves both parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input exactly as it was fetched.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Solved)` - The answers to part one and part two, and how long each phase took.
+    /// * `Err(ParseError)` - If the normalized input could not be parsed.
+    pub fn solve(&self, input: Input) -> Result<Solved, ParseError> {
+        (self.solve)(input)
+    }
+
+    /// Runs the parse and each part repeatedly and summarizes how long they took.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input exactly as it was fetched.
+    /// * `settings` - How many times each phase is run.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Report)` - The statistics of each phase.
+    /// * `Err(ParseError)` - If the normalized input could not be parsed.
+    pub fn bench(&self, input: Input, settings: &Settings) -> Result<Report, ParseError> {
+        let [parse, part1, part2] = (self.bench)(input, settings)?;
+        Ok(Report {
+            year: self.year,
+            day: self.day,
+            name: self.name.to_string(),
+            warmup: settings.warmup,
+            parse,
+            part1,
+            part2,
+        })
+    }
+}
+
+/// Normalizes and parses the input for a solution, then solves both parts from the one parsed
+/// form, timing each phase on its own.
+pub fn solve<S: Solution>(input: Input) -> Result<Solved, ParseError> {
+    let start = Instant::now();
+    let parsed = S::parse(&input.normalize(S::NORMALIZATION))?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let part1 = S::part1(&parsed).into();
+    let part1_time = start.elapsed();
+
+    let start = Instant::now();
+    let part2 = S::part2(&parsed).into();
+    let part2_time = start.elapsed();
+
+    Ok(Solved {
+        part1,
+        part2,
+        timings: Timings {
+            parse,
+            part1: part1_time,
+            part2: part2_time,
+        },
+    })
+}
+
+static REGISTRY: Mutex<Vec<Registration>> = Mutex::new(Vec::new());
+
+/// Adds a solution to the registry. Solutions are normally registered with `register_solution!`
+/// while the program starts. Registering a second solution for the same year and day panics, as
+/// there would be no telling which of the two `find` returns.
+pub fn register(registration: Registration) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = registry
+        .iter()
+        .find(|s| s.year == registration.year && s.day == registration.day)
+    {
+        panic!(
+            "Both {} and {} are registered as the solution of {} day {}",
+            existing.name, registration.name, registration.year, registration.day
+        );
+    }
+    registry.push(registration);
+}
+
+/// Every registered solution, ordered by year and day.
+pub fn solutions() -> Vec<Registration> {
+    let mut solutions = REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    solutions.sort_by_key(|s| (s.year, s.day));
+    solutions
+}
+
+/// Finds the solution registered for a year and day.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+///
+/// # Returns
+///
+/// * `Option<Registration>` - The solution, `None` if no linked crate registered one.
+pub fn find(year: &i32, day: &i32) -> Option<Registration> {
+    solutions()
+        .into_iter()
+        .find(|s| s.year == *year && s.day == *day)
+}
+
+/// Registers a solution for a year and day so the runner can find it without being edited.
+///
+/// ```ignore
+/// aoc::register_solution!(2025, 1, Day1);
+/// ```
+#[macro_export]
+macro_rules! register_solution {
+    ($year:expr, $day:expr, $solution:ty) => {
+        const _: () = {
+            #[$crate::__ctor::ctor]
+            fn register() {
+                $crate::solution::register($crate::solution::Registration::new::<$solution>(
+                    $year,
+                    $day,
+                    stringify!($solution),
+                ));
+            }
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sums;
+
+    impl Solution for Sums {
+        type Parsed = Vec<i64>;
+        type Part1 = i64;
+        type Part2 = Answer;
+
+        const NORMALIZATION: Normalization = Normalization::TrimAll;
+
+        fn parse(input: &Input) -> Result<Vec<i64>, ParseError> {
+            assert!(!input.raw().starts_with(' '));
+            match input.lines().iter().position(|line| line.contains('x')) {
+                Some(row) => Err(ParseError::new(row + 1, 1, "x", "Expected a number")),
+                None => input.ints(),
+            }
+        }
+
+        fn part1(parsed: &Vec<i64>) -> i64 {
+            parsed.iter().sum()
+        }
+
+        fn part2(parsed: &Vec<i64>) -> Answer {
+            Answer::Text(format!("{:?}", parsed))
+        }
+    }
+
+    crate::register_solution!(1001, 1, Sums);
+
+    #[test]
+    fn registered_solution_found() {
+        let solution = find(&1001, &1).unwrap();
+        assert_eq!(solution.name, "Sums");
+        let solved = solution.solve(Input::from(" 2\n3\n4\n")).unwrap();
+        assert_eq!(solved.part1, 9);
+        assert_eq!(solved.part2, "[2, 3, 4]");
+        assert!(find(&1001, &2).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Both Sums and Again are registered as the solution of 1001 day 1")]
+    fn duplicate_registration_rejected() {
+        register(Registration::new::<Sums>(1001, 1, "Again"));
+    }
+
+    #[test]
+    fn parse_error_returned() {
+        let solution = find(&1001, &1).unwrap();
+        let result = solution.solve(Input::from("2\nx\n")).unwrap_err();
+        assert_eq!((result.line, result.text.as_str()), (2, "x"));
+    }
+
+    #[test]
+    fn solutions_ordered() {
+        register(Registration::new::<Sums>(1000, 25, "Earlier"));
+        let solutions = solutions();
+        let position = |year| solutions.iter().position(|s| s.year == year).unwrap();
+        assert!(position(1000) < position(1001));
+    }
+
+    #[test]
+    fn solution_benchmarked() {
+        let solution = find(&1001, &1).unwrap();
+        let settings = Settings {
+            warmup: 2,
+            iterations: 5,
+        };
+        let report = solution.bench(Input::from("2\n3\n"), &settings).unwrap();
+        assert_eq!((report.year, report.day, report.warmup), (1001, 1, 2));
+        assert!(report.phases().iter().all(|(_, s)| s.iterations == 5));
+        assert!(solution.bench(Input::from("x"), &settings).is_err());
+    }
+
+    #[test]
+    fn timings_totalled() {
+        let timings = Timings {
+            parse: Duration::from_millis(3),
+            part1: Duration::from_millis(5),
+            part2: Duration::from_millis(7),
+        };
+        assert_eq!(timings.total(), Duration::from_millis(15));
+    }
+}
@@ -0,0 +1,349 @@
+use crate::ledger::{Ledger, Refusal};
+use crate::{error, html, http, unlock, Answer, AocError, Context};
+use std::fmt;
+use std::time::Duration;
+
+/// The outcome of submitting an answer to the Advent of Code website.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited { wait: Duration },
+    AlreadySolved,
+    WrongLevel,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "Correct"),
+            Verdict::TooHigh => write!(f, "Wrong (too high)"),
+            Verdict::TooLow => write!(f, "Wrong (too low)"),
+            Verdict::Wrong => write!(f, "Wrong"),
+            Verdict::RateLimited { wait } => write!(f, "Rate limited, wait {:?}", wait),
+            Verdict::AlreadySolved => write!(f, "Already solved"),
+            Verdict::WrongLevel => write!(f, "Wrong level"),
+        }
+    }
+}
+
+/// Submits an answer for the specified year, day and part.
+///
+/// The answer is first checked against the ledger of earlier guesses and withheld if it repeats a
+/// wrong answer or falls outside the bounds set by earlier "too high" and "too low" verdicts.
+/// Every verdict returned by the site is recorded in the ledger.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `part` - The part of the puzzle being answered, either 1 or 2.
+/// * `answer` - The answer to submit, which is withheld if the part is unsolved.
+/// * `context` - Defines the context to interact with the Advent of Code website.
+///
+/// # Returns
+///
+/// * `Ok(Verdict)` - The verdict the site returned for the answer.
+/// * `Err(AocError)` - If the answer was withheld, could not be submitted or the response was not understood.
+pub async fn submit_answer(
+    year: &i32,
+    day: &i32,
+    part: &i32,
+    answer: &Answer,
+    context: &Context,
+) -> Result<Verdict, AocError> {
+    unlock::check_unlocked(year, day, &unlock::SystemClock)?;
+
+    let Some(answer) = answer.submission() else {
+        return Err(AocError::Refused {
+            answer: answer.to_string(),
+            refusal: Refusal::Unsolved,
+        });
+    };
+    let answer = answer.as_str();
+
+    let mut ledger = Ledger::load(year, day, context)?;
+    ledger
+        .check(part, answer)
+        .map_err(|refusal| AocError::Refused {
+            answer: answer.to_string(),
+            refusal,
+        })?;
+
+    let path = format!("{}{}/day/{}/answer", context.url, year, day);
+    println!("Submitting {} for part {} to {}", answer, part, path);
+
+    let level = part.to_string();
+    let response = http::post_form(
+        &path,
+        &[("level", level.as_str()), ("answer", answer)],
+        context,
+    )
+    .await?;
+    let body = error::read_body(response, year, Some(day)).await?;
+
+    let verdict = parse_verdict(&body).ok_or_else(|| AocError::MalformedResponse {
+        url: path.clone(),
+        reason: "The response did not contain a recognised verdict".to_string(),
+    })?;
+    let verdict = match verdict {
+        Verdict::WrongLevel => {
+            // the site reports the same message for an already solved part and a locked part
+            let path = format!("{}{}/day/{}", context.url, year, day);
+            let response = http::get(&path, context).await?;
+            let page = error::read_body(response, year, Some(day)).await?;
+            if count_solved_parts(&page) >= *part as usize {
+                Verdict::AlreadySolved
+            } else {
+                Verdict::WrongLevel
+            }
+        }
+        verdict => verdict,
+    };
+
+    ledger.record(part, answer, &verdict)?;
+    Ok(verdict)
+}
+
+/// Interprets the page returned after submitting an answer.
+///
+/// # Arguments
+///
+/// * `page` - A string slice that holds the HTML returned by the answer endpoint.
+///
+/// # Returns
+///
+/// * `Some(Verdict)` - If the page carries a recognised verdict.
+/// * `None` - If the page does not contain a verdict.
+pub fn parse_verdict(page: &str) -> Option<Verdict> {
+    let text = html::to_text(html::articles(page).first()?);
+
+    if text.contains("That's the right answer") {
+        Some(Verdict::Correct)
+    } else if text.contains("You don't seem to be solving the right level") {
+        Some(Verdict::WrongLevel)
+    } else if text.contains("You gave an answer too recently") {
+        Some(Verdict::RateLimited {
+            wait: parse_wait(&text).unwrap_or_default(),
+        })
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Some(Verdict::TooHigh)
+        } else if text.contains("your answer is too low") {
+            Some(Verdict::TooLow)
+        } else {
+            Some(Verdict::Wrong)
+        }
+    } else {
+        None
+    }
+}
+
+/// Reads the remaining lockout from a message such as `You have 4m 32s left to wait`.
+fn parse_wait(text: &str) -> Option<Duration> {
+    let start = text.find("You have ")? + "You have ".len();
+    let end = start + text[start..].find(" left to wait")?;
+
+    text[start..end]
+        .split_whitespace()
+        .map(|token| {
+            let (value, unit) = token.split_at(token.len() - 1);
+            let value = value.parse::<u64>().ok()?;
+            match unit {
+                "h" => Some(value * 3600),
+                "m" => Some(value * 60),
+                "s" => Some(value),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+/// Counts the parts of a puzzle page that already show an accepted answer.
+pub(crate) fn count_solved_parts(page: &str) -> usize {
+    page.matches("Your puzzle answer was").count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_context;
+    use std::fs;
+
+    fn build_context(server: &mockito::Server, name: &str) -> Context {
+        let data_dir = std::env::temp_dir().join("aoc_submit_test").join(name);
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir).unwrap();
+        }
+        test_context(&server.url(), data_dir)
+    }
+
+    #[test]
+    fn correct_parsed() {
+        let result = parse_verdict(include_str!("fixtures/answer_correct.html"));
+        assert_eq!(result, Some(Verdict::Correct));
+    }
+
+    #[test]
+    fn too_high_parsed() {
+        let result = parse_verdict(include_str!("fixtures/answer_too_high.html"));
+        assert_eq!(result, Some(Verdict::TooHigh));
+    }
+
+    #[test]
+    fn too_low_parsed() {
+        let result = parse_verdict(include_str!("fixtures/answer_too_low.html"));
+        assert_eq!(result, Some(Verdict::TooLow));
+    }
+
+    #[test]
+    fn wrong_parsed() {
+        let result = parse_verdict(include_str!("fixtures/answer_wrong.html"));
+        assert_eq!(result, Some(Verdict::Wrong));
+    }
+
+    #[test]
+    fn rate_limited_parsed() {
+        let result = parse_verdict(include_str!("fixtures/answer_rate_limited.html"));
+        assert_eq!(
+            result,
+            Some(Verdict::RateLimited {
+                wait: Duration::from_secs(272)
+            })
+        );
+    }
+
+    #[test]
+    fn wrong_level_parsed() {
+        let result = parse_verdict(include_str!("fixtures/answer_wrong_level.html"));
+        assert_eq!(result, Some(Verdict::WrongLevel));
+    }
+
+    #[test]
+    fn unrecognised_response_rejected() {
+        let result = parse_verdict("<html><article><p>Something else</p></article></html>");
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn solved_parts_counted() {
+        assert_eq!(
+            count_solved_parts(include_str!("fixtures/day_part1.html")),
+            0
+        );
+        assert_eq!(
+            count_solved_parts(include_str!("fixtures/day_complete.html")),
+            2
+        );
+    }
+
+    // this is not using tokio::test due to runtime block_on collisions when using mockito
+    #[test]
+    fn test_submit_answer() {
+        let mut server = mockito::Server::new();
+
+        let _m = server
+            .mock("POST", "/2023/day/1/answer")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("level".into(), "1".into()),
+                mockito::Matcher::UrlEncoded("answer".into(), "1097".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "text/html")
+            .with_body(include_str!("fixtures/answer_too_low.html"))
+            .create();
+
+        let context = build_context(&server, "test_submit_answer");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(submit_answer(&2023, &1, &1, &Answer::from(1097), &context));
+
+        assert_eq!(result.unwrap(), Verdict::TooLow);
+
+        let ledger = Ledger::load(&2023, &1, &context).unwrap();
+        assert_eq!(ledger.guesses(&1).count(), 1);
+    }
+
+    #[test]
+    fn submit_refused_by_ledger() {
+        let mut server = mockito::Server::new();
+
+        let answer = server.mock("POST", "/2023/day/1/answer").expect(0).create();
+
+        let context = build_context(&server, "submit_refused_by_ledger");
+        let mut ledger = Ledger::load(&2023, &1, &context).unwrap();
+        ledger.record(&1, "1000", &Verdict::TooHigh).unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(submit_answer(&2023, &1, &1, &Answer::from(1200), &context));
+
+        assert!(matches!(result, Err(AocError::Refused { .. })));
+        answer.assert();
+    }
+
+    #[test]
+    fn submit_solved_part() {
+        let mut server = mockito::Server::new();
+
+        let _answer = server
+            .mock("POST", "/2023/day/1/answer")
+            .with_status(200)
+            .with_body(include_str!("fixtures/answer_wrong_level.html"))
+            .create();
+        let _page = server
+            .mock("GET", "/2023/day/1")
+            .with_status(200)
+            .with_body(include_str!("fixtures/day_complete.html"))
+            .create();
+
+        let context = build_context(&server, "submit_solved_part");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(submit_answer(&2023, &1, &2, &Answer::from(5678), &context));
+
+        assert_eq!(result.unwrap(), Verdict::AlreadySolved);
+    }
+
+    #[test]
+    fn submit_locked_part() {
+        let mut server = mockito::Server::new();
+
+        let _answer = server
+            .mock("POST", "/2023/day/1/answer")
+            .with_status(200)
+            .with_body(include_str!("fixtures/answer_wrong_level.html"))
+            .create();
+        let _page = server
+            .mock("GET", "/2023/day/1")
+            .with_status(200)
+            .with_body(include_str!("fixtures/day_part1.html"))
+            .create();
+
+        let context = build_context(&server, "submit_locked_part");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(submit_answer(&2023, &1, &2, &Answer::from(5678), &context));
+
+        assert_eq!(result.unwrap(), Verdict::WrongLevel);
+    }
+
+    #[test]
+    fn submit_unsolved_part() {
+        let mut server = mockito::Server::new();
+
+        let answer = server.mock("POST", "/2023/day/1/answer").expect(0).create();
+
+        let context = build_context(&server, "submit_unsolved_part");
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(submit_answer(&2023, &1, &1, &Answer::Unsolved, &context));
+
+        assert!(matches!(
+            result,
+            Err(AocError::Refused {
+                refusal: Refusal::Unsolved,
+                ..
+            })
+        ));
+        answer.assert();
+    }
+}
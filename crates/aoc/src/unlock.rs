@@ -0,0 +1,301 @@
+use crate::AocError;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The first year Advent of Code was run.
+pub const FIRST_YEAR: i32 = 2015;
+
+/// Puzzles unlock at midnight US Eastern, which is always standard time in December.
+const UNLOCK_OFFSET_SECONDS: u64 = 5 * 60 * 60;
+
+/// A source of the current time, so the unlock schedule can be tested against a fixed instant.
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+/// The clock of the machine the code is running on.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// The number of puzzles in the event for the specified year.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+///
+/// # Returns
+///
+/// * `i32` - 12 from 2025 onwards, 25 for the earlier events.
+pub fn days_in_event(year: &i32) -> i32 {
+    if *year >= 2025 {
+        12
+    } else {
+        25
+    }
+}
+
+/// Calculates when the puzzle for the specified year and day unlocks.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+///
+/// # Returns
+///
+/// * `Ok(SystemTime)` - Midnight US Eastern on the day of the puzzle.
+/// * `Err(AocError)` - If the event does not have a puzzle for that year and day.
+pub fn unlock_time(year: &i32, day: &i32) -> Result<SystemTime, AocError> {
+    if *year < FIRST_YEAR || *day < 1 || *day > days_in_event(year) {
+        return Err(AocError::NoSuchPuzzle {
+            year: *year,
+            day: *day,
+        });
+    }
+
+    let days = days_from_civil(*year as i64, 12, *day as i64) as u64;
+    Ok(UNIX_EPOCH + Duration::from_secs(days * 24 * 60 * 60 + UNLOCK_OFFSET_SECONDS))
+}
+
+/// Calculates how long remains until the puzzle unlocks.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `clock` - The source of the current time.
+///
+/// # Returns
+///
+/// * `Ok(Duration)` - The time remaining, zero once the puzzle has unlocked.
+/// * `Err(AocError)` - If the event does not have a puzzle for that year and day.
+pub fn time_until_unlock(year: &i32, day: &i32, clock: &impl Clock) -> Result<Duration, AocError> {
+    let unlock = unlock_time(year, day)?;
+    Ok(unlock.duration_since(clock.now()).unwrap_or_default())
+}
+
+/// Refuses puzzles that have not unlocked yet.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `clock` - The source of the current time.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the puzzle is available.
+/// * `Err(AocError)` - If the puzzle does not exist or has not unlocked yet.
+pub fn check_unlocked(year: &i32, day: &i32, clock: &impl Clock) -> Result<(), AocError> {
+    if time_until_unlock(year, day, clock)?.is_zero() {
+        Ok(())
+    } else {
+        Err(AocError::NotUnlocked {
+            year: *year,
+            day: *day,
+        })
+    }
+}
+
+/// Waits until the puzzle unlocks, reporting the remaining time as it counts down.
+///
+/// # Arguments
+///
+/// * `year` - The year of the event.
+/// * `day` - The day of the event.
+/// * `clock` - The source of the current time.
+///
+/// # Returns
+///
+/// * `Ok(())` - Once the puzzle has unlocked.
+/// * `Err(AocError)` - If the event does not have a puzzle for that year and day.
+pub async fn wait_for_unlock(year: &i32, day: &i32, clock: &impl Clock) -> Result<(), AocError> {
+    loop {
+        let remaining = time_until_unlock(year, day, clock)?;
+        if remaining.is_zero() {
+            println!("AOC {} Day {} unlocked", year, day);
+            return Ok(());
+        }
+
+        println!(
+            "AOC {} Day {} unlocks in {}",
+            year,
+            day,
+            format_countdown(&remaining)
+        );
+        // report every minute until the last minute, then every second
+        let step = match remaining.as_secs() {
+            0..=60 => Duration::from_secs(1),
+            _ => Duration::from_secs(60),
+        };
+        tokio::time::sleep(step.min(remaining)).await;
+    }
+}
+
+/// Formats a duration as `[Nd ]HH:MM:SS`.
+pub fn format_countdown(remaining: &Duration) -> String {
+    let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    match days {
+        0 => clock,
+        _ => format!("{}d {}", days, clock),
+    }
+}
+
+/// Formats a moment as `YYYY-MM-DD HH:MM:SS UTC`.
+pub fn format_timestamp(time: &SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+    let seconds = seconds.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Converts days since 1970-01-01 to a date in the proleptic Gregorian calendar, the inverse of
+/// `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Counts the days from 1970-01-01 to the specified date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedClock(SystemTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            self.0
+        }
+    }
+
+    fn at(seconds: u64) -> FixedClock {
+        FixedClock(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn unlock_at_midnight_eastern() {
+        let result = unlock_time(&2025, &1).unwrap();
+        assert_eq!(result, UNIX_EPOCH + Duration::from_secs(1764565200));
+
+        let result = unlock_time(&2015, &25).unwrap();
+        assert_eq!(result, UNIX_EPOCH + Duration::from_secs(1451019600));
+    }
+
+    #[test]
+    fn event_length_by_year() {
+        assert_eq!(days_in_event(&2024), 25);
+        assert_eq!(days_in_event(&2025), 12);
+        assert!(unlock_time(&2024, &25).is_ok());
+        assert!(matches!(
+            unlock_time(&2025, &13),
+            Err(AocError::NoSuchPuzzle {
+                year: 2025,
+                day: 13
+            })
+        ));
+        assert!(matches!(
+            unlock_time(&2014, &1),
+            Err(AocError::NoSuchPuzzle { .. })
+        ));
+        assert!(matches!(
+            unlock_time(&2025, &0),
+            Err(AocError::NoSuchPuzzle { .. })
+        ));
+    }
+
+    #[test]
+    fn locked_before_midnight() {
+        let clock = at(1764565200 - 1);
+        assert!(matches!(
+            check_unlocked(&2025, &1, &clock),
+            Err(AocError::NotUnlocked { year: 2025, day: 1 })
+        ));
+        assert_eq!(
+            time_until_unlock(&2025, &1, &clock).unwrap(),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn unlocked_from_midnight() {
+        assert!(check_unlocked(&2025, &1, &at(1764565200)).is_ok());
+        assert!(check_unlocked(&2025, &1, &at(1764565200 + 3600)).is_ok());
+        assert_eq!(
+            time_until_unlock(&2025, &1, &at(1764565200 + 3600)).unwrap(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn wait_returns_once_unlocked() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(wait_for_unlock(&2025, &1, &at(1764565200)));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn timestamp_formatted() {
+        let result = format_timestamp(&(UNIX_EPOCH + Duration::from_secs(1764565200)));
+        assert_eq!(result, "2025-12-01 05:00:00 UTC");
+        let result = format_timestamp(&(UNIX_EPOCH + Duration::from_secs(951782400 + 59)));
+        assert_eq!(result, "2000-02-29 00:00:59 UTC");
+        assert_eq!(format_timestamp(&UNIX_EPOCH), "1970-01-01 00:00:00 UTC");
+    }
+
+    #[test]
+    fn countdown_formatted() {
+        assert_eq!(format_countdown(&Duration::from_secs(59)), "00:00:59");
+        assert_eq!(format_countdown(&Duration::from_secs(3661)), "01:01:01");
+        assert_eq!(
+            format_countdown(&Duration::from_secs(2 * 86400 + 5)),
+            "2d 00:00:05"
+        );
+        assert_eq!(format_countdown(&Duration::from_millis(1500)), "00:00:02");
+    }
+}
@@ -1,7 +1,9 @@
 extern crate alloc;
 
+use aoc::{Input, Normalization, ParseError, Solution};
+
 #[derive(Debug)]
-struct Turn {
+pub struct Turn {
     dir: Direction,
     click: i64,
 }
@@ -12,22 +14,46 @@ enum Direction {
     Right,
 }
 
-fn parse(input: &Vec<String>) -> Vec<Turn> {
+fn parse(input: &Input) -> Result<Vec<Turn>, ParseError> {
     let mut turns: Vec<Turn> = Vec::new();
 
-    input.iter().for_each(|v| {
-        let dir = match v.chars().nth(0).unwrap() {
+    for (i, line) in input.lines().into_iter().enumerate() {
+        let Some(first) = line.chars().next() else {
+            return Err(ParseError::new(
+                i + 1,
+                1,
+                "",
+                "Expected a rotation such as L68",
+            ));
+        };
+        let (dir, click) = line.split_at(first.len_utf8());
+        let dir = match first {
             'L' => Direction::Left,
             'R' => Direction::Right,
-            other => panic!("Unable to determine direction {}", other),
+            _ => {
+                return Err(ParseError::new(
+                    i + 1,
+                    1,
+                    dir,
+                    "Unable to determine direction",
+                ))
+            }
         };
 
-        let click = v[1..].parse::<i64>().unwrap();
+        let click = click.parse::<i64>().map_err(|_| {
+            ParseError::at(
+                i + 1,
+                line,
+                first.len_utf8(),
+                click,
+                "Expected a number of clicks",
+            )
+        })?;
 
         turns.push(Turn { dir, click });
-    });
+    }
 
-    turns
+    Ok(turns)
 }
 
 fn turn_left(current: i64, amount: i64) -> i64 {
@@ -58,66 +84,88 @@ fn turn_right_with_overflow(current: i64, amount: i64) -> (i64, i64) {
     (new, overflow)
 }
 
-pub fn part1(input: &Vec<String>) -> i64 {
-    let turns = parse(input);
-    println!("starting at 50");
-    let result = turns.iter().fold((50i64, 0i64), |acc, x| {
-        let new = match x.dir {
-            Direction::Left => turn_left(acc.0, x.click),
-            Direction::Right => turn_right(acc.0, x.click),
-        };
-        let count = match new {
-            0 => acc.1 + 1,
-            _ => acc.1,
-        };
-        println!("turn {:?} {} clicks to {}", x.dir, x.click, new);
-        (new, count)
-    });
+pub struct Day1;
 
-    result.1
-}
+aoc::register_solution!(2025, 1, Day1);
 
-pub fn part2(input: &Vec<String>) -> i64 {
-    let turns = parse(input);
-    println!("starting at 50");
-    let result = turns.iter().fold((50i64, 0i64), |acc, x| {
-        let new = match x.dir {
-            Direction::Left => turn_left_with_overflow(acc.0, x.click),
-            Direction::Right => turn_right_with_overflow(acc.0, x.click),
-        };
-        let count = match new.0 {
-            0 => acc.1 + 1,
-            _ => acc.1,
-        } + new.1;
-        println!("turn {:?} {} clicks to {} - overflow {}", x.dir, x.click, new.0, new.1);
-        (new.0, count)
-    });
-
-    result.1
+impl Solution for Day1 {
+    type Parsed = Vec<Turn>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    /// Each rotation is on a line of its own.
+    const NORMALIZATION: Normalization = Normalization::TrimTrailingNewline;
+
+    fn parse(input: &Input) -> Result<Vec<Turn>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(turns: &Vec<Turn>) -> i64 {
+        println!("starting at 50");
+        let result = turns.iter().fold((50i64, 0i64), |acc, x| {
+            let new = match x.dir {
+                Direction::Left => turn_left(acc.0, x.click),
+                Direction::Right => turn_right(acc.0, x.click),
+            };
+            let count = match new {
+                0 => acc.1 + 1,
+                _ => acc.1,
+            };
+            println!("turn {:?} {} clicks to {}", x.dir, x.click, new);
+            (new, count)
+        });
+
+        result.1
+    }
+
+    fn part2(turns: &Vec<Turn>) -> i64 {
+        println!("starting at 50");
+        let result = turns.iter().fold((50i64, 0i64), |acc, x| {
+            let new = match x.dir {
+                Direction::Left => turn_left_with_overflow(acc.0, x.click),
+                Direction::Right => turn_right_with_overflow(acc.0, x.click),
+            };
+            let count = match new.0 {
+                0 => acc.1 + 1,
+                _ => acc.1,
+            } + new.1;
+            println!(
+                "turn {:?} {} clicks to {} - overflow {}",
+                x.dir, x.click, new.0, new.1
+            );
+            (new.0, count)
+        });
+
+        result.1
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_sample() -> Vec<String> {
-        include_str!("sample.dat")
-            .lines()
-            .map(|line| line.to_string())
-            .collect()
+    fn get_sample() -> Input {
+        Input::from(include_str!("sample.dat")).normalize(Day1::NORMALIZATION)
     }
 
     #[test]
     fn sample_part1_valid() {
         let sample = get_sample();
-        let result = part1(&sample);
+        let result = Day1::part1(&Day1::parse(&sample).unwrap());
         assert_eq!(result, 3);
     }
 
     #[test]
     fn sample_part2_valid() {
         let sample = get_sample();
-        let result = part2(&sample);
+        let result = Day1::part2(&Day1::parse(&sample).unwrap());
         assert_eq!(result, 6);
     }
+
+    #[test]
+    fn unknown_direction_located() {
+        let result = Day1::parse(&Input::from("L68\nR30\nX48")).unwrap_err();
+        assert_eq!((result.line, result.column), (3, 1));
+        assert_eq!(result.text, "X");
+    }
 }
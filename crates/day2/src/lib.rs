@@ -1,23 +1,30 @@
+use aoc::{Input, Normalization, ParseError, Solution};
 use std::collections::HashMap;
 
 #[derive(Debug)]
-struct Range {
+pub struct Range {
     min: i64,
     max: i64,
 }
 
-fn parse(input: &Vec<String>) -> Vec<Range> {
-    input
-        .iter()
-        .map(|x| x.split(","))
-        .flatten()
-        .map(|x| {
-            let parts = x.split('-').collect::<Vec<_>>();
-            let min = parts[0].parse::<i64>().unwrap();
-            let max = parts[1].parse::<i64>().unwrap();
-            Range { min, max }
+fn parse(input: &Input) -> Result<Vec<Range>, ParseError> {
+    let ids = input.ints()?;
+    if ids.len() % 2 != 0 {
+        return Err(ParseError::new(
+            input.lines().len() + 1,
+            1,
+            "",
+            "Expected a range such as 11-22 for every pair of product IDs",
+        ));
+    }
+
+    Ok(ids
+        .chunks(2)
+        .map(|bounds| Range {
+            min: bounds[0],
+            max: bounds[1],
         })
-        .collect()
+        .collect())
 }
 
 fn find_doubles(range: &Range) -> Vec<i64> {
@@ -57,50 +64,72 @@ fn find_repeats(range: &Range) -> Vec<i64> {
         .collect()
 }
 
-pub fn part1(input: &Vec<String>) -> i64 {
-    let ranges = parse(input);
-    let doubles = ranges
-        .iter()
-        .map(|r| find_doubles(&r))
-        .flatten()
-        .collect::<Vec<_>>();
-    //println!("{:?}", doubles);
-    doubles.iter().sum()
-}
-// 36862281460 too high
-pub fn part2(input: &Vec<String>) -> i64 {
-    let ranges = parse(input);
-    let ids = ranges
-        .iter()
-        .map(|r| find_repeats(&r))
-        .flatten()
-        .collect::<Vec<_>>();
-    println!("{:?}", ids);
-    ids.iter().sum()
+pub struct Day2;
+
+aoc::register_solution!(2025, 2, Day2);
+
+impl Solution for Day2 {
+    type Parsed = Vec<Range>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    /// The ranges are read as integers, whitespace around them does not matter.
+    const NORMALIZATION: Normalization = Normalization::TrimTrailingNewline;
+
+    fn parse(input: &Input) -> Result<Vec<Range>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(ranges: &Vec<Range>) -> i64 {
+        let doubles = ranges
+            .iter()
+            .map(|r| find_doubles(&r))
+            .flatten()
+            .collect::<Vec<_>>();
+        //println!("{:?}", doubles);
+        doubles.iter().sum()
+    }
+
+    fn part2(ranges: &Vec<Range>) -> i64 {
+        let ids = ranges
+            .iter()
+            .map(|r| find_repeats(&r))
+            .flatten()
+            .collect::<Vec<_>>();
+        println!("{:?}", ids);
+        ids.iter().sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_sample() -> Vec<String> {
-        include_str!("sample.dat")
-            .lines()
-            .map(|line| line.to_string())
-            .collect()
+    fn get_sample() -> Input {
+        Input::from(include_str!("sample.dat")).normalize(Day2::NORMALIZATION)
     }
 
     #[test]
     fn sample_part1_valid() {
         let sample = get_sample();
-        let result = part1(&sample);
+        let result = Day2::part1(&Day2::parse(&sample).unwrap());
         assert_eq!(result, 1227775554);
     }
 
     #[test]
     fn sample_part2_valid() {
         let sample = get_sample();
-        let result = part2(&sample);
+        let result = Day2::part2(&Day2::parse(&sample).unwrap());
         assert_eq!(result, 4174379265);
     }
+
+    #[test]
+    fn invalid_id_located() {
+        let result = Day2::parse(&Input::from("11-22,95-99999999999999999999")).unwrap_err();
+        assert_eq!((result.line, result.column), (1, 10));
+        assert_eq!(result.text, "99999999999999999999");
+
+        let result = Day2::parse(&Input::from("11-22,95")).unwrap_err();
+        assert_eq!((result.line, result.column), (2, 1));
+    }
 }
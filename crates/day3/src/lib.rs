@@ -1,8 +1,36 @@
-fn parse(input: &Vec<String>) -> Vec<Vec<i32>> {
-    input
-        .iter()
-        .map(|x| x.chars().map(|y| y.to_digit(10).unwrap() as i32).collect())
-        .collect()
+use aoc::{Input, Normalization, ParseError, Solution};
+
+fn parse(input: &Input) -> Result<Vec<Vec<i32>>, ParseError> {
+    let mut banks = vec![];
+
+    for (row, bank) in input.grid::<char>()?.into_iter().enumerate() {
+        if bank.len() < 12 {
+            let text = bank.iter().collect::<String>();
+            return Err(ParseError::new(
+                row + 1,
+                1,
+                text,
+                "Expected at least 12 batteries",
+            ));
+        }
+        let joltages = bank
+            .iter()
+            .enumerate()
+            .map(|(column, c)| {
+                c.to_digit(10).map(|d| d as i32).ok_or_else(|| {
+                    ParseError::new(
+                        row + 1,
+                        column + 1,
+                        c.to_string(),
+                        "Expected a joltage digit",
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        banks.push(joltages);
+    }
+
+    Ok(banks)
 }
 
 fn find_largest_2_joltage(batteries: &Vec<i32>) -> i64 {
@@ -85,71 +113,93 @@ fn find_largest_12_joltage(batteries: &Vec<i32>) -> i64 {
     s.parse::<i64>().unwrap()
 }
 
-pub fn part1(input: &Vec<String>) -> i64 {
-    let banks = parse(input);
-    let joltages = banks
-        .iter()
-        .map(|x| find_largest_2_joltage(x))
-        .collect::<Vec<i64>>();
-    joltages.iter().sum()
-}
+pub struct Day3;
 
+aoc::register_solution!(2025, 3, Day3);
 
-// 173843907657110 too low
-// 173960689460215 too high
-pub fn part2(input: &Vec<String>) -> i64 {
-    let banks = parse(input);
-    let joltages = banks
-        .iter()
-        .map(|x| find_largest_12_joltage(x))
-        .collect::<Vec<i64>>();
-    println!("{:?}", joltages);
-    joltages.iter().sum()
+impl Solution for Day3 {
+    type Parsed = Vec<Vec<i32>>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    /// Each bank of batteries is a line of digits.
+    const NORMALIZATION: Normalization = Normalization::TrimTrailingNewline;
+
+    fn parse(input: &Input) -> Result<Vec<Vec<i32>>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(banks: &Vec<Vec<i32>>) -> i64 {
+        let joltages = banks
+            .iter()
+            .map(|x| find_largest_2_joltage(x))
+            .collect::<Vec<i64>>();
+        joltages.iter().sum()
+    }
+
+    fn part2(banks: &Vec<Vec<i32>>) -> i64 {
+        let joltages = banks
+            .iter()
+            .map(|x| find_largest_12_joltage(x))
+            .collect::<Vec<i64>>();
+        println!("{:?}", joltages);
+        joltages.iter().sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_sample() -> Vec<String> {
-        include_str!("sample.dat")
-            .lines()
-            .map(|line| line.to_string())
-            .collect()
+    fn get_sample() -> Input {
+        Input::from(include_str!("sample.dat")).normalize(Day3::NORMALIZATION)
     }
 
     #[test]
     fn sample_part1_valid() {
         let sample = get_sample();
-        let result = part1(&sample);
+        let result = Day3::part1(&Day3::parse(&sample).unwrap());
         assert_eq!(result, 357);
     }
 
     #[test]
     fn sample_part2_valid() {
         let sample = get_sample();
-        let result = part2(&sample);
+        let result = Day3::part2(&Day3::parse(&sample).unwrap());
         assert_eq!(result, 3121910778619);
     }
 
 
     #[test]
     fn nine_heavy() {
-        let sample = vec!["9847555865827676657688569759758758555445794968796565767687569857578579948785658658585576858447769967".to_string()];
-        let result = part2(&sample);
+        let sample = Input::from(
+            "9847555865827676657688569759758758555445794968796565767687569857578579948785658658585576858447769967",
+        );
+        let result = Day3::part2(&Day3::parse(&sample).unwrap());
         assert_eq!(result, 999999999997);
     }
     #[test]
     fn mixed_compaction() {
-        let sample = vec!["4238131312372266238322323992851888128123122242282434742712328963325226342472212242762454422754113423".to_string()];
-        let result = part2(&sample);
+        let sample = Input::from(
+            "4238131312372266238322323992851888128123122242282434742712328963325226342472212242762454422754113423",
+        );
+        let result = Day3::part2(&Day3::parse(&sample).unwrap());
         assert_eq!(result, 999777543423);
     }
 
     #[test]
     fn full_last_12() {
-        let sample =vec!["2222544122212134422245322622323232121113423222422311232232422322223221212233652414122759322232231122".to_string()];
-        let result = part2(&sample);
+        let sample = Input::from(
+            "2222544122212134422245322622323232121113423222422311232232422322223221212233652414122759322232231122",
+        );
+        let result = Day3::part2(&Day3::parse(&sample).unwrap());
         assert_eq!(result, 932232231122);
     }
+
+    #[test]
+    fn invalid_joltage_located() {
+        let result = Day3::parse(&Input::from("987654321111111\n81111111111111x")).unwrap_err();
+        assert_eq!((result.line, result.column), (2, 15));
+        assert_eq!(result.text, "x");
+    }
 }
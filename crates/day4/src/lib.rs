@@ -1,25 +1,42 @@
+use aoc::{Input, Normalization, ParseError, Solution};
+
 #[derive(Debug,Clone)]
-struct Floor {
+pub struct Floor {
     occupied: bool,
 }
 
-fn parse(input: &Vec<String>) -> Vec<Vec<Floor>> {
-    input
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '.' => Floor {
-                        occupied: false,
-                    },
-                    '@' => Floor {
-                        occupied: true,
-                    },
-                    x => panic!("Unexpected floor tile {} detected", x),
-                })
-                .collect()
-        })
-        .collect()
+impl TryFrom<char> for Floor {
+    type Error = ();
+
+    fn try_from(c: char) -> Result<Floor, ()> {
+        match c {
+            '.' => Ok(Floor { occupied: false }),
+            '@' => Ok(Floor { occupied: true }),
+            _ => Err(()),
+        }
+    }
+}
+
+fn parse(input: &Input) -> Result<Vec<Vec<Floor>>, ParseError> {
+    let floor = input.grid::<Floor>().map_err(|e| ParseError {
+        reason: "Unexpected floor tile".to_string(),
+        ..e
+    })?;
+
+    let Some(width) = floor.first().map(Vec::len).filter(|w| *w > 0) else {
+        return Err(ParseError::new(1, 1, "", "Expected a floor plan"));
+    };
+    if let Some(row) = floor.iter().position(|row| row.len() != width) {
+        let line = input.lines()[row];
+        return Err(ParseError::new(
+            row + 1,
+            1,
+            line,
+            format!("Expected a row of {} tiles", width),
+        ));
+    }
+
+    Ok(floor)
 }
 
 fn count_has_four_spaces(floor: &Vec<Vec<Floor>>) -> i64 {
@@ -126,50 +143,70 @@ fn count_and_remove(floor: &Vec<Vec<Floor>>) -> (i64,Vec<Vec<Floor>>) {
     (accessible, result)
 }
 
-pub fn part1(input: &Vec<String>) -> i64 {
-    let floor = parse(input);
-    count_has_four_spaces(&floor)
-}
+pub struct Day4;
 
-pub fn part2(input: &Vec<String>) -> i64 {
-    let mut floor = parse(input);
-    let mut total_removed = 0i64;
-    let mut cleaning = true;
-
-    while cleaning {
-        let (removed, new_floor) = count_and_remove(&floor);
-        total_removed += removed;
-        floor = new_floor;
-        if removed == 0 {
-            cleaning = false;
-        }
+aoc::register_solution!(2025, 4, Day4);
+
+impl Solution for Day4 {
+    type Parsed = Vec<Vec<Floor>>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    /// The floor plan is a grid with one line per row.
+    const NORMALIZATION: Normalization = Normalization::TrimTrailingNewline;
+
+    fn parse(input: &Input) -> Result<Vec<Vec<Floor>>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(floor: &Vec<Vec<Floor>>) -> i64 {
+        count_has_four_spaces(floor)
     }
 
-    total_removed
+    fn part2(floor: &Vec<Vec<Floor>>) -> i64 {
+        let mut floor = floor.clone();
+        let mut total_removed = 0i64;
+        let mut cleaning = true;
+
+        while cleaning {
+            let (removed, new_floor) = count_and_remove(&floor);
+            total_removed += removed;
+            floor = new_floor;
+            if removed == 0 {
+                cleaning = false;
+            }
+        }
+
+        total_removed
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_sample() -> Vec<String> {
-        include_str!("sample.dat")
-            .lines()
-            .map(|line| line.to_string())
-            .collect()
+    fn get_sample() -> Input {
+        Input::from(include_str!("sample.dat")).normalize(Day4::NORMALIZATION)
     }
 
     #[test]
     fn sample_part1_valid() {
         let sample = get_sample();
-        let result = part1(&sample);
+        let result = Day4::part1(&Day4::parse(&sample).unwrap());
         assert_eq!(result, 13);
     }
 
     #[test]
     fn sample_part2_valid() {
         let sample = get_sample();
-        let result = part2(&sample);
+        let result = Day4::part2(&Day4::parse(&sample).unwrap());
         assert_eq!(result, 43);
     }
+
+    #[test]
+    fn truncated_row_located() {
+        let result = Day4::parse(&Input::from("..@@.\n@@@.@\n@@")).unwrap_err();
+        assert_eq!((result.line, result.column), (3, 1));
+        assert_eq!(result.reason, "Expected a row of 5 tiles");
+    }
 }
@@ -1,3 +1,5 @@
+use aoc::{Input, Normalization, ParseError, Solution};
+
 #[derive(Debug, Clone)]
 struct Lot {
     start: i64,
@@ -5,50 +7,73 @@ struct Lot {
 }
 
 #[derive(Debug)]
-struct Inventory {
+pub struct Inventory {
     fresh: Vec<Lot>,
     available: Vec<i64>,
 }
 
-fn parse(input: &Vec<String>) -> Inventory {
-    let mut fresh = vec![];
-    let mut available = vec![];
-
-    let mut reading_fresh = true;
-
-    for i in 0..input.len() {
-        if reading_fresh && input[i] == "" {
-            reading_fresh = false;
-        } else if reading_fresh {
-            if let Some((a, b)) = input[i].split_once('-') {
-                let start = a.trim().parse::<i64>().expect("invalid start");
-                let end = b.trim().parse::<i64>().expect("invalid end");
-                fresh.push(Lot { start, end });
-            } else {
-                panic!("invalid range format");
-            }
-        } else {
-            available.push(input[i].parse::<i64>().unwrap());
-        }
-    }
-
-    Inventory { fresh, available }
+fn parse_lot(input: &Input, line: &str) -> Result<Lot, ParseError> {
+    let Some((a, b)) = line.split_once('-') else {
+        return Err(ParseError::within(
+            input,
+            line,
+            0,
+            line,
+            "Expected a range such as 3-5",
+        ));
+    };
+    let bound = |offset: usize, text: &str| {
+        let trimmed = text.trim();
+        let offset = offset + (text.len() - text.trim_start().len());
+        trimmed.parse::<i64>().map_err(|_| {
+            ParseError::within(input, line, offset, trimmed, "Expected an ingredient ID")
+        })
+    };
+    Ok(Lot {
+        start: bound(0, a)?,
+        end: bound(a.len() + 1, b)?,
+    })
 }
 
-pub fn part1(input: &Vec<String>) -> i64 {
-    let inventory = parse(input);
-    let mut fresh = 0i64;
-
-    for ingredient in &inventory.available {
-        for lot in &inventory.fresh {
-            if lot.start <= *ingredient && lot.end >= *ingredient {
-                fresh += 1;
-                break;
-            }
+fn parse(input: &Input) -> Result<Inventory, ParseError> {
+    let blocks = input.blocks();
+    let (ranges, ingredients) = match blocks.as_slice() {
+        [ranges, ingredients] => (ranges, ingredients),
+        [_, _, extra, ..] => {
+            return Err(ParseError::within(
+                input,
+                extra[0],
+                0,
+                extra[0],
+                "Expected only the fresh ranges and the available ingredients",
+            ));
         }
-    }
-
-    fresh
+        _ => {
+            return Err(ParseError::new(
+                input.lines().len() + 1,
+                1,
+                "",
+                "Expected a blank line before the available ingredients",
+            ));
+        }
+    };
+
+    let fresh = ranges
+        .iter()
+        .map(|line| parse_lot(input, line))
+        .collect::<Result<_, _>>()?;
+    let available = ingredients
+        .iter()
+        .map(|line| {
+            let text = line.trim();
+            let offset = line.len() - line.trim_start().len();
+            text.parse::<i64>().map_err(|_| {
+                ParseError::within(input, line, offset, text, "Expected an ingredient ID")
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Inventory { fresh, available })
 }
 
 fn condense_ranges(lots: &Vec<Lot>) -> (bool, Vec<Lot>) {
@@ -102,50 +127,90 @@ fn condense_ranges(lots: &Vec<Lot>) -> (bool, Vec<Lot>) {
     (modified, new_lots)
 }
 
-pub fn part2(input: &Vec<String>) -> i64 {
-    let inventory = parse(input);
+pub struct Day5;
 
-    let mut lots = inventory.fresh;
-    lots.sort_by_key(|l| l.start);
-    let mut compacting = true;
+aoc::register_solution!(2025, 5, Day5);
 
-    while compacting {
-        let (modified, new_lots) = condense_ranges(&lots);
-        lots = new_lots;
-        compacting = modified;
+impl Solution for Day5 {
+    type Parsed = Inventory;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    /// The blank line separating the fresh ranges from the available ingredients is kept.
+    const NORMALIZATION: Normalization = Normalization::TrimTrailingNewline;
+
+    fn parse(input: &Input) -> Result<Inventory, ParseError> {
+        parse(input)
     }
 
-    //println!("{:?}", lots);
-    lots.sort_by_key(|l| l.start);
-    for l in &lots {
-        println!("{:?}", l);
+    fn part1(inventory: &Inventory) -> i64 {
+        let mut fresh = 0i64;
+
+        for ingredient in &inventory.available {
+            for lot in &inventory.fresh {
+                if lot.start <= *ingredient && lot.end >= *ingredient {
+                    fresh += 1;
+                    break;
+                }
+            }
+        }
+
+        fresh
     }
 
-    lots.iter().map(|lot| lot.end - lot.start + 1).sum::<i64>()
+    fn part2(inventory: &Inventory) -> i64 {
+        let mut lots = inventory.fresh.clone();
+        lots.sort_by_key(|l| l.start);
+        let mut compacting = true;
+
+        while compacting {
+            let (modified, new_lots) = condense_ranges(&lots);
+            lots = new_lots;
+            compacting = modified;
+        }
+
+        //println!("{:?}", lots);
+        lots.sort_by_key(|l| l.start);
+        for l in &lots {
+            println!("{:?}", l);
+        }
+
+        lots.iter().map(|lot| lot.end - lot.start + 1).sum::<i64>()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_sample() -> Vec<String> {
-        include_str!("sample.dat")
-            .lines()
-            .map(|line| line.to_string())
-            .collect()
+    fn get_sample() -> Input {
+        Input::from(include_str!("sample.dat")).normalize(Day5::NORMALIZATION)
     }
 
     #[test]
     fn sample_part1_valid() {
         let sample = get_sample();
-        let result = part1(&sample);
+        let result = Day5::part1(&Day5::parse(&sample).unwrap());
         assert_eq!(result, 3);
     }
 
     #[test]
     fn sample_part2_valid() {
         let sample = get_sample();
-        let result = part2(&sample);
+        let result = Day5::part2(&Day5::parse(&sample).unwrap());
         assert_eq!(result, 14);
     }
+
+    #[test]
+    fn missing_ingredients_reported() {
+        let result = Day5::parse(&Input::from("3-5\n10-14")).unwrap_err();
+        assert_eq!((result.line, result.column), (3, 1));
+
+        let result = Day5::parse(&Input::from("3-5\n10-1a\n\n1")).unwrap_err();
+        assert_eq!((result.line, result.column), (2, 4));
+        assert_eq!(result.text, "1a");
+
+        let result = Day5::parse(&Input::from("3-5\n\n1\n\n 2x")).unwrap_err();
+        assert_eq!((result.line, result.column), (5, 1));
+    }
 }
@@ -1,3 +1,5 @@
+use aoc::{Input, Normalization, ParseError, Solution};
+
 #[derive(Debug, Clone)]
 enum MathOperand {
     Multiply,
@@ -20,32 +22,72 @@ fn transpose(matrix: &Vec<Vec<i64>>) -> Vec<Vec<i64>> {
     transposed
 }
 
-fn get_operands(input: &String) -> Vec<MathOperand> {
-    input
-        .split_ascii_whitespace()
-        .map(|c| match c {
-            "*" => MathOperand::Multiply,
-            "+" => MathOperand::Add,
-            x => panic!("Unknown operand {} specified", x),
+/// The words of a line separated by whitespace, each with the byte offset it starts at.
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut words = vec![];
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, c.is_ascii_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                words.push((s, &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+fn get_operands(number: usize, line: &str) -> Result<Vec<MathOperand>, ParseError> {
+    words(line)
+        .into_iter()
+        .map(|(offset, c)| match c {
+            "*" => Ok(MathOperand::Multiply),
+            "+" => Ok(MathOperand::Add),
+            x => Err(ParseError::at(number, line, offset, x, "Unknown operand")),
         })
         .collect()
 }
 
-fn parse(input: &Vec<String>) -> Vec<MathOp> {
+/// Splits the worksheet into the rows of numbers and the operands on the last line.
+fn split_operands<'a>(
+    lines: &'a [&'a str],
+) -> Result<(&'a [&'a str], Vec<MathOperand>), ParseError> {
+    match lines.split_last() {
+        Some((last, rows)) if !rows.is_empty() => Ok((rows, get_operands(lines.len(), last)?)),
+        _ => Err(ParseError::new(
+            1,
+            1,
+            "",
+            "Expected rows of numbers above the operands",
+        )),
+    }
+}
+
+fn parse(input: &Input) -> Result<Vec<MathOp>, ParseError> {
+    let input = input.lines();
     let mut ops = vec![];
 
-    let operands: Vec<MathOperand> = get_operands(input.last().unwrap());
+    let (rows, operands) = split_operands(&input)?;
 
-    let matrix = transpose(
-        &input[..input.len() - 1]
-            .iter()
-            .map(|l| {
-                l.split_ascii_whitespace()
-                    .map(|c| c.parse::<i64>().unwrap())
-                    .collect()
+    let mut numbers = vec![];
+    for (i, line) in rows.iter().enumerate() {
+        let row = words(line)
+            .into_iter()
+            .map(|(offset, c)| {
+                c.parse::<i64>()
+                    .map_err(|_| ParseError::at(i + 1, line, offset, c, "Expected a number"))
             })
-            .collect(),
-    );
+            .collect::<Result<Vec<_>, _>>()?;
+        if row.len() != operands.len() {
+            let reason = format!("Expected {} numbers, one per operand", operands.len());
+            return Err(ParseError::new(i + 1, 1, *line, reason));
+        }
+        numbers.push(row);
+    }
+
+    let matrix = transpose(&numbers);
 
     let rows = matrix.len();
 
@@ -56,62 +98,63 @@ fn parse(input: &Vec<String>) -> Vec<MathOp> {
         });
     }
 
-    ops
+    Ok(ops)
 }
 
-fn parse_vertically(input: &Vec<String>) -> Vec<MathOp> {
+fn parse_vertically(input: &Input) -> Result<Vec<MathOp>, ParseError> {
     let mut ops = vec![];
 
-    let operands: Vec<MathOperand> = get_operands(input.last().unwrap());
+    let lines = input.lines();
+    let (rows, operands) = split_operands(&lines)?;
+    let mut operands = operands.into_iter();
+    let mut next_operand = |column: usize| {
+        operands.next().ok_or_else(|| {
+            ParseError::new(
+                lines.len(),
+                column + 1,
+                "",
+                "Expected an operand for the problem",
+            )
+        })
+    };
 
-    let mut index = 0;
-    let mut grouping = 0;
     let mut factors = vec![];
 
-    let cols = *vec![input[0].len(), input[1].len(), input[2].len()]
-        .iter()
-        .max()
-        .unwrap();
-
-    while index < cols {
-        let mut factor = vec![];
-        
-        for r in 0..input.len() - 1 {
-            let chars = input[r].chars().collect::<Vec<char>>();
-
-            factor.push(match index < chars.len()   {
-                true => chars[index],
-                _ => ' ',
-            });
-        }
-        index += 1;
-        if factor.iter().all(|c| c.is_whitespace()) {
+    // only the digits above the operands, trailing spaces after the operands add no problem
+    let columns = Input::from(rows.join("\n")).columns();
+    for (i, factor) in columns.iter().enumerate() {
+        if factor.trim().is_empty() {
             // group boundary found
             ops.push(MathOp {
                 factors: factors.clone(),
-                operand: operands[grouping].clone(),
+                operand: next_operand(i)?,
             });
-            grouping += 1;
             factors.clear();
         } else {
             println!("Captured {:?}", factor);
-            factors.push(
-                factor
-                    .clone()
-                    .into_iter()
-                    .collect::<String>()
-                    .trim()
-                    .parse::<i64>()
-                    .unwrap(),
-            );
+            let number = factor.trim();
+            let value = number.parse::<i64>().map_err(|_| {
+                // the row of the first character that is not a digit, or of the first digit of a
+                // number too large to fit
+                let (row, text) = factor
+                    .chars()
+                    .enumerate()
+                    .find(|(_, c)| !c.is_ascii_digit() && *c != ' ')
+                    .map(|(row, c)| (row, c.to_string()))
+                    .unwrap_or_else(|| {
+                        let row = factor.chars().take_while(|c| *c == ' ').count();
+                        (row, number.to_string())
+                    });
+                ParseError::new(row + 1, i + 1, text, "Expected a number")
+            })?;
+            factors.push(value);
         }
-        factor.clear();
     }
     ops.push(MathOp {
         factors: factors.clone(),
-        operand: operands[grouping].clone(),
+        operand: next_operand(columns.len())?,
     });
-    ops
+    Ok(ops)
 }
 
 fn calculate_worksheet(worksheet: &Vec<MathOp>) -> i64 {
@@ -132,39 +175,83 @@ fn calculate_worksheet(worksheet: &Vec<MathOp>) -> i64 {
         .sum()
 }
 
-pub fn part1(input: &Vec<String>) -> i64 {
-    let worksheet = parse(input);
-    calculate_worksheet(&worksheet)
+/// The worksheet read both ways, row by row for part one and column by column for part two.
+#[derive(Debug)]
+pub struct Worksheets {
+    horizontal: Vec<MathOp>,
+    vertical: Vec<MathOp>,
 }
 
-pub fn part2(input: &Vec<String>) -> i64 {
-    let worksheet = parse_vertically(input);
-    println!("worksheet:\n{:?}", worksheet);
-    calculate_worksheet(&worksheet)
+pub struct Day6;
+
+aoc::register_solution!(2025, 6, Day6);
+
+impl Solution for Day6 {
+    type Parsed = Worksheets;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    /// The operands are aligned in columns, so every leading and trailing space matters.
+    const NORMALIZATION: Normalization = Normalization::Keep;
+
+    fn parse(input: &Input) -> Result<Worksheets, ParseError> {
+        Ok(Worksheets {
+            horizontal: parse(input)?,
+            vertical: parse_vertically(input)?,
+        })
+    }
+
+    fn part1(worksheets: &Worksheets) -> i64 {
+        calculate_worksheet(&worksheets.horizontal)
+    }
+
+    fn part2(worksheets: &Worksheets) -> i64 {
+        println!("worksheet:\n{:?}", worksheets.vertical);
+        calculate_worksheet(&worksheets.vertical)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_sample() -> Vec<String> {
-        include_str!("sample.dat")
-            .lines()
-            .map(|line| line.to_string())
-            .collect()
+    fn get_sample() -> Input {
+        Input::from(include_str!("sample.dat")).normalize(Day6::NORMALIZATION)
     }
 
     #[test]
     fn sample_part1_valid() {
         let sample = get_sample();
-        let result = part1(&sample);
+        let result = Day6::part1(&Day6::parse(&sample).unwrap());
         assert_eq!(result, 4277556);
     }
 
     #[test]
     fn sample_part2_valid() {
         let sample = get_sample();
-        let result = part2(&sample);
+        let result = Day6::part2(&Day6::parse(&sample).unwrap());
         assert_eq!(result, 3263827);
     }
+
+    #[test]
+    fn padded_operands_ignored() {
+        let input = Input::from("123 328\n 45 64\n*   +     \n").normalize(Day6::NORMALIZATION);
+        let worksheets = Day6::parse(&input).unwrap();
+        assert_eq!(Day6::part1(&worksheets), 123 * 45 + 328 + 64);
+        assert_eq!(Day6::part2(&worksheets), 24 * 35 + 36 + 24 + 8);
+    }
+
+    #[test]
+    fn vertical_digit_located() {
+        let result = parse_vertically(&Input::from("123 328\n 4x 64\n*   +  ")).unwrap_err();
+        assert_eq!((result.line, result.column), (2, 3));
+        assert_eq!(result.text, "x");
+    }
+
+    #[test]
+    fn unknown_operand_located() {
+        let result = Day6::parse(&Input::from("123 328\n 45 64\n*   /")).unwrap_err();
+        assert_eq!((result.line, result.column), (3, 5));
+        assert_eq!(result.text, "/");
+    }
 }
@@ -1,7 +1,7 @@
 use std::env;
-use std::fs::File;
-use std::io::{Result, Write};
-use std::path::PathBuf;
+use std::fs;
+use std::io::Result;
+use std::path::{Path, PathBuf};
 
 fn main() -> Result<()> {
     println!("Copy .session file to target directory");
@@ -10,10 +10,26 @@ fn main() -> Result<()> {
     let session_path = PathBuf::from("../../.session");
 
     if session_path.exists() {
-        File::create(out.join(".session"))?
-            .write_all(include_bytes!("../../.session"))?;
+        fs::copy(&session_path, out.join(".session"))?;
     } else {
         println!("cargo::warning=.session not found, Create the file with the session key prior to execution");
     }
-    Ok(())
+
+    link_solutions(out)
+}
+
+/// Writes an `extern crate` for every path dependency other than `aoc`, so the solution crates are
+/// linked even though `main` never names them.
+fn link_solutions(out: &Path) -> Result<()> {
+    let manifest = fs::read_to_string("Cargo.toml")?;
+    let crates = manifest
+        .lines()
+        .filter_map(|line| {
+            let (name, spec) = line.split_once('=')?;
+            let name = name.trim();
+            (spec.contains("path") && name != "aoc").then(|| name.replace('-', "_"))
+        })
+        .map(|name| format!("extern crate {};\n", name))
+        .collect::<String>();
+    fs::write(out.join("solutions.rs"), crates)
 }
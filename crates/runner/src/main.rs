@@ -1,83 +1,1134 @@
-use aoc;
+use aoc::AocError;
 use getopts::Options;
+use std::error::Error;
 use std::process;
 use url::Url;
 
+// links every solution crate listed in Cargo.toml so their solutions register themselves
+include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+
+/// Reports an error along with its causes and exits with a code identifying the kind of failure.
+fn fail(error: AocError) -> ! {
+    println!("Error: {}", error);
+    let mut source = error.source();
+    while let Some(cause) = source {
+        println!("  caused by: {}", cause);
+        source = cause.source();
+    }
+
+    let code = match error {
+        AocError::Network { .. } => 10,
+        AocError::Http { .. } => 11,
+        AocError::Unauthorized { .. } => {
+            println!("Refresh the session ID and try again.");
+            12
+        }
+        AocError::InvalidProfile { .. } => 20,
+        AocError::InvalidSession { .. } => {
+            println!("Copy the value of the session cookie from adventofcode.com and try again.");
+            19
+        }
+        AocError::NotUnlocked { .. } => 13,
+        AocError::Cache { .. } => 14,
+        AocError::MalformedResponse { .. } => 15,
+        AocError::Refused { .. } => 16,
+        AocError::Io { .. } => 17,
+        AocError::NoSuchPuzzle { .. } => 18,
+        AocError::NotCached { .. } => 21,
+        AocError::Offline { .. } => 22,
+        AocError::Encryption { .. } => {
+            println!(
+                "Set {} or copy the key file shared by the team and try again.",
+                aoc::crypt::KEY_ENV
+            );
+            23
+        }
+    };
+    process::exit(code);
+}
+
 #[tokio::main]
 async fn main() {
     let args: Vec<String> = std::env::args().collect();
     let program = args[0].clone();
 
     let mut opts = Options::new();
-    opts.optflag("", "profile", "");
+    opts.optopt(
+        "",
+        "profile",
+        "Use the session and cache of a named profile instead of the default one",
+        "NAME",
+    );
     opts.optflag("", "manifest-path", "");
+    opts.optopt(
+        "",
+        "contact",
+        "An email address or URL added to the User-Agent, defaults to AOC_CONTACT",
+        "CONTACT",
+    );
+    opts.optopt(
+        "",
+        "cache-dir",
+        "The root of the cache, defaults to AOC_CACHE_DIR, the Data directory of the workspace or the user cache directory",
+        "DIR",
+    );
+    opts.optopt(
+        "",
+        "key-file",
+        "The key of the encrypted cache, defaults to AOC_CACHE_KEY or the user config directory",
+        "FILE",
+    );
+    opts.optopt(
+        "",
+        "migrate-cache",
+        "Move 2025.day1.dat style files from DIR into the nested layout of the cache root",
+        "DIR",
+    );
     opts.optflag("h", "help", "print this help menu");
+    opts.optflag("", "list", "List the years and days with a solution");
+    opts.optflag(
+        "",
+        "all",
+        "Run every solution of YEAR and print a summary, continuing past days that fail",
+    );
+    opts.optopt(
+        "",
+        "jobs",
+        "The days --all runs at the same time, defaults to the number of CPUs",
+        "N",
+    );
+    opts.optflag(
+        "v",
+        "verbose",
+        "Show what every day printed with --all, not only failed days",
+    );
+    opts.optflag("", "worker", "Solve YEAR DAY from standard input for --all");
+    opts.optflag(
+        "",
+        "verify",
+        "Rerun the solutions with known answers, optionally only of YEAR [DAY], and fail on any mismatch",
+    );
+    opts.optopt(
+        "",
+        "answers",
+        "The file of known answers, defaults to answers.txt in this or a parent directory",
+        "FILE",
+    );
+    opts.optflag(
+        "",
+        "confirm",
+        "Record the answers of YEAR DAY as known answers once they are confirmed correct",
+    );
     opts.optflag("y", "year", "The year of the advent of code");
     opts.optflag("d", "day", "The day of the advent of code to run");
+    opts.optopt(
+        "s",
+        "submit",
+        "Submit the computed answer for part 1 or 2",
+        "PART",
+    );
+    opts.optflag(
+        "",
+        "check-session",
+        "Confirm the session is still signed in before fetching anything",
+    );
+    opts.optflag(
+        "",
+        "offline",
+        "Never contact the site, only use cached inputs and report the ones missing",
+    );
+    opts.optopt(
+        "",
+        "prefetch",
+        "Download every unlocked input of the years into the cache, such as 2025 or 2015-2025",
+        "YEARS",
+    );
+    opts.optflag(
+        "",
+        "wait",
+        "Wait for the puzzle to unlock before fetching the input",
+    );
+    opts.optflag(
+        "",
+        "bench",
+        "Time the parse and each part over many runs instead of running the solution once",
+    );
+    opts.optopt(
+        "",
+        "warmup",
+        "The runs discarded before measuring a benchmark, default 3",
+        "N",
+    );
+    opts.optopt(
+        "",
+        "iterations",
+        "The runs measured by a benchmark, default 20",
+        "N",
+    );
+    opts.optopt(
+        "",
+        "save-baseline",
+        "Save the benchmark as a JSON baseline",
+        "FILE",
+    );
+    opts.optopt(
+        "",
+        "baseline",
+        "Compare the benchmark with a saved baseline and fail on a significant regression",
+        "FILE",
+    );
+    opts.optopt(
+        "",
+        "leaderboard",
+        "Print the standings of a private leaderboard and the star timeline for the day",
+        "ID",
+    );
+    opts.optflag(
+        "",
+        "describe",
+        "Print the puzzle description instead of running the solution",
+    );
+    opts.optflag(
+        "",
+        "scaffold",
+        "Extract the puzzle sample into crates/dayN instead of running the solution",
+    );
 
     let matches = match opts.parse(&args[1..]) {
         Ok(m) => m,
         Err(f) => {
-            println!("{}", f.to_string());
+            println!("{}", f);
             process::exit(1);
         }
     };
 
     if matches.opt_present("h") {
-        let brief = format!("Usage: {} [options]", program);
+        let brief = format!(
+            "Usage: {0} [options] YEAR DAY\n       {0} [options] cache list|show YEAR DAY|purge [YEAR [DAY]]|export FILE|import FILE\n       {0} [options] cache keygen|encrypt|rotate",
+            program
+        );
         print!("{}", opts.usage(&brief));
         process::exit(0);
     }
 
-    let session_id = match aoc::session::get_session_id(&std::env::current_dir().unwrap()) {
-        Some(id) => id,
-        None => {
-            println!("Session ID not found. Please create a .session file in the current directory with your session ID.");
+    let cache_dir = matches
+        .opt_str("cache-dir")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(aoc::cache::default_root);
+
+    if let Some(from) = matches.opt_str("migrate-cache") {
+        let from = std::path::PathBuf::from(from);
+        match aoc::cache::migrate(&from, &cache_dir) {
+            Ok(migration) => {
+                for (source, destination) in &migration.moved {
+                    println!("Moved {} to {}", source.display(), destination.display());
+                }
+                for source in &migration.skipped {
+                    println!(
+                        "Kept {}, the nested layout already has this file",
+                        source.display()
+                    );
+                }
+                println!("Migrated {} files", migration.moved.len());
+            }
+            Err(e) => fail(e),
+        }
+        process::exit(0);
+    }
+
+    let key_file = matches
+        .opt_str("key-file")
+        .map(std::path::PathBuf::from)
+        .or_else(aoc::crypt::key_file);
+    let key = aoc::crypt::load_key(key_file.as_deref()).unwrap_or_else(|e| fail(e));
+
+    if matches.free.first().map(String::as_str) == Some("cache") {
+        let root = std::env::current_dir().unwrap().join(&cache_dir);
+        let profile = matches.opt_str("profile");
+        cache_command(
+            &matches.free[1..],
+            profile.as_deref(),
+            &root,
+            key,
+            key_file.as_deref(),
+        );
+    }
+
+    if matches.opt_present("worker") {
+        worker(&matches);
+    }
+
+    if matches.opt_present("list") {
+        for solution in aoc::solution::solutions() {
+            println!(
+                "AOC {} Day {:>2}  {}",
+                solution.year, solution.day, solution.name
+            );
+        }
+        process::exit(0);
+    }
+
+    let profile = matches.opt_str("profile");
+    let offline = matches.opt_present("offline");
+    let session_id = match aoc::session::find_session(
+        &std::env::current_dir().unwrap(),
+        profile.as_deref(),
+    ) {
+        Ok(Some(session)) => {
+            println!("Using session from {}", session.source);
+            session.id
+        }
+        // only the cache is used, so there is nothing to authenticate
+        Err(_) | Ok(None) if offline => String::new(),
+        Err(e) => fail(e),
+        Ok(None) => {
+            println!(
+                "Session ID not found. Set {}, create a {} file in this or a parent directory, or create {}.",
+                aoc::session::session_env(profile.as_deref()),
+                aoc::session::session_file_name(profile.as_deref()),
+                aoc::session::config_file(profile.as_deref())
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|| "a session file in the user config directory".to_string())
+            );
             process::exit(2);
         }
     };
 
+
+    let mut http = aoc::HttpConfig::default();
+    if let Some(contact) = matches
+        .opt_str("contact")
+        .or_else(|| std::env::var("AOC_CONTACT").ok())
+    {
+        http = http.with_contact(&contact);
+    }
+
+    let context = aoc::Context::new(
+        session_id,
+        std::env::current_dir().unwrap().join(cache_dir),
+        Url::parse("https://adventofcode.com").unwrap(),
+    )
+    .with_profile(profile)
+    .with_http(http)
+    .with_offline(offline)
+    .with_key(key);
+
+    if matches.opt_present("check-session") {
+        if let Err(e) = aoc::session::validate_session(&context).await {
+            fail(e);
+        }
+        println!("Session is signed in");
+    }
+
+    if let Some(years) = matches.opt_str("prefetch") {
+        let Some(years) = parse_years(&years) else {
+            println!(
+                "Unable to prefetch {}, expected years such as 2025, 2023,2024 or 2015-2025",
+                years
+            );
+            process::exit(7);
+        };
+        let clock = aoc::unlock::SystemClock;
+        let fetches = match aoc::prefetch::prefetch(&years, &context, &clock).await {
+            Ok(fetches) => fetches,
+            Err(e) => fail(e),
+        };
+        let mut failed = 0;
+        for fetch in &fetches {
+            match &fetch.outcome {
+                aoc::prefetch::Outcome::Cached => {}
+                aoc::prefetch::Outcome::Fetched => {
+                    println!("AOC {} Day {} fetched", fetch.year, fetch.day)
+                }
+                aoc::prefetch::Outcome::Failed(e) => {
+                    failed += 1;
+                    println!("AOC {} Day {} failed: {}", fetch.year, fetch.day, e)
+                }
+            }
+        }
+        println!(
+            "{} inputs cached, {} failed",
+            fetches.len() - failed,
+            failed
+        );
+        process::exit(if failed == 0 { 0 } else { 8 });
+    }
+
+    let answers = matches
+        .opt_str("answers")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| aoc::known::find_answers_file(&std::env::current_dir().unwrap()));
+
+    if matches.opt_present("verify") {
+        let (Ok(year), Ok(day)) = (
+            matches.free.first().map(|y| y.parse::<i32>()).transpose(),
+            matches.free.get(1).map(|d| d.parse::<i32>()).transpose(),
+        ) else {
+            println!("Unable to verify, expected --verify [YEAR [DAY]]");
+            process::exit(1);
+        };
+        verify(
+            &answers,
+            year,
+            day,
+            &context,
+            parse_jobs(&matches),
+            matches.opt_present("verbose"),
+        )
+        .await;
+    }
+
     let year = match matches.opt_get::<i32>("y") {
         Ok(Some(y)) => y,
-        _ => {
-            args[1].parse().unwrap()
-        }
+        _ => matches.free[0].parse().unwrap(),
     };
 
+    if matches.opt_present("all") {
+        run_all(
+            &year,
+            &context,
+            parse_jobs(&matches),
+            matches.opt_present("verbose"),
+        )
+        .await;
+    }
+
     let day = match matches.opt_get::<i32>("d") {
         Ok(Some(d)) => d,
-        _ => {
-            args[2].parse().unwrap()
-        }
+        _ => matches.free[1].parse().unwrap(),
     };
 
 
-    let context = aoc::Context {
-        url: Url::parse("https://adventofcode.com").unwrap(),
-        data_dir: std::env::current_dir().unwrap().join("Data"),
-        session_id,
-    };
+    if let Some(id) = matches.opt_str("leaderboard") {
+        let Ok(id) = id.parse::<u64>() else {
+            println!("Unable to show leaderboard {}, expected a numeric id", id);
+            process::exit(6);
+        };
+        let leaderboard = match aoc::leaderboard::get_leaderboard(&year, &id, &context).await {
+            Ok(leaderboard) => leaderboard,
+            Err(e) => fail(e),
+        };
+        println!("Standings");
+        print!("{}", aoc::leaderboard::render_standings(&leaderboard));
+        println!();
+        println!("Day {} stars, time since unlock (part 1, part 2)", day);
+        match aoc::leaderboard::render_timeline(&leaderboard, &year, &day) {
+            Ok(timeline) => print!("{}", timeline),
+            Err(e) => fail(e),
+        }
+        process::exit(0);
+    }
+
+    let crate_dir = std::env::current_dir()
+        .unwrap()
+        .join("crates")
+        .join(format!("day{}", day));
+
+    if matches.opt_present("describe") {
+        match aoc::puzzle::get_description(&year, &day, &context).await {
+            Ok(description) => {
+                println!("{}", description);
+                save_description(&crate_dir, &description);
+            }
+            Err(e) => fail(e),
+        }
+        process::exit(0);
+    }
+
+    if matches.opt_present("scaffold") {
+        let description = match aoc::puzzle::get_description(&year, &day, &context).await {
+            Ok(description) => description,
+            Err(e) => fail(e),
+        };
+        let sample = match aoc::puzzle::get_page_from_cache(&year, &day, &context) {
+            Ok(Some(page)) => aoc::sample::extract_sample(&page),
+            Ok(None) => None,
+            Err(e) => fail(e),
+        };
+        let Some(sample) = sample else {
+            println!("No example found in the puzzle description");
+            process::exit(5);
+        };
+        match aoc::sample::scaffold_day(&crate_dir, &year, &day, &sample) {
+            Ok(written) => written
+                .iter()
+                .for_each(|f| println!("Wrote {}", f.display())),
+            Err(e) => fail(e),
+        }
+        save_description(&crate_dir, &description);
+        let manifest = crate_dir.with_file_name("runner").join("Cargo.toml");
+        match link_solution(&manifest, &format!("day{}", day)) {
+            Ok(true) => println!("Added day{} to {}", day, manifest.display()),
+            Ok(false) => {}
+            Err(e) => fail(AocError::Io {
+                path: manifest,
+                source: e,
+            }),
+        }
+        process::exit(0);
+    }
+
+    if matches.opt_present("wait") {
+        if let Err(e) = aoc::unlock::wait_for_unlock(&year, &day, &aoc::unlock::SystemClock).await {
+            fail(e);
+        }
+    }
+
     let input = aoc::get_input(&year, &day, &context).await;
     match input {
-        Ok(lines) => {
-            let start_time = std::time::Instant::now();
-            let (part1, part2) = match day {
-                1 => (day1::part1(&lines), day1::part2(&lines)),
-                2 => (day2::part1(&lines), day2::part2(&lines)),
-                3 => (day3::part1(&lines), day3::part2(&lines)),
-                4 => (day4::part1(&lines), day4::part2(&lines)),
-                5 => (day5::part1(&lines), day5::part2(&lines)),
-                6 => (day6::part1(&lines), day6::part2(&lines)),
-                _ => {
-                    println!("Day {} not implemented", day);
-                    process::exit(3);
+        Ok(input) => {
+            let Some(solution) = aoc::solution::find(&year, &day) else {
+                println!("Day {} not implemented", day);
+                process::exit(3);
+            };
+            if matches.opt_present("bench") {
+                let settings = parse_settings(&matches);
+                let save = matches
+                    .opt_str("save-baseline")
+                    .map(std::path::PathBuf::from);
+                let baseline = matches.opt_str("baseline").map(std::path::PathBuf::from);
+                bench(
+                    &solution,
+                    input,
+                    &settings,
+                    save.as_deref(),
+                    baseline.as_deref(),
+                );
+            }
+
+            let source = input.clone().normalize(solution.normalization);
+            let solved = match solution.solve(input) {
+                Ok(solved) => solved,
+                Err(e) => report_parse_error(&day, &e, &source),
+            };
+            let timings = solved.timings;
+            println!(
+                "Elapsed: {:?} (parse {:?}, part 1 {:?}, part 2 {:?})",
+                timings.total(),
+                timings.parse,
+                timings.part1,
+                timings.part2
+            );
+            println!("Part 1: {}", solved.part1);
+            println!("Part 2: {}", solved.part2);
+
+            if matches.opt_present("confirm") {
+                let profile = context.profile.as_deref();
+                record_answer(&answers, &year, &day, 1, profile, &solved.part1);
+                record_answer(&answers, &year, &day, 2, profile, &solved.part2);
+            }
+
+            if let Some(part) = matches.opt_str("s") {
+                let (part, answer) = match part.as_str() {
+                    "1" => (1, solved.part1),
+                    "2" => (2, solved.part2),
+                    other => {
+                        println!("Unable to submit part {}, expected 1 or 2", other);
+                        process::exit(4);
+                    }
+                };
+                match aoc::submit_answer(&year, &day, &part, &answer, &context).await {
+                    Ok(verdict) => {
+                        println!("Verdict: {}", verdict);
+                        if verdict == aoc::Verdict::Correct {
+                            let profile = context.profile.as_deref();
+                            record_answer(&answers, &year, &day, part, profile, &answer);
+                        }
+                    }
+                    Err(e) => fail(e),
+                }
+            }
+        }
+        Err(e @ AocError::NotCached { .. }) => {
+            report_missing(&year, &context);
+            fail(e)
+        }
+        Err(e) => fail(e),
+    }
+}
+
+/// Solves the day named by the free arguments from the input on standard input, or benchmarks it
+/// with `--bench`, reporting the outcome to the run that started this process, and exits.
+fn worker(matches: &getopts::Matches) -> ! {
+    let free = &matches.free;
+    let (Some(Ok(year)), Some(Ok(day))) = (
+        free.first().map(|y| y.parse::<i32>()),
+        free.get(1).map(|d| d.parse::<i32>()),
+    ) else {
+        println!("Usage: --worker [--bench] YEAR DAY");
+        process::exit(1);
+    };
+    let Some(solution) = aoc::solution::find(&year, &day) else {
+        println!("Day {} not implemented", day);
+        process::exit(3);
+    };
+    let mut input = String::new();
+    if let Err(e) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut input) {
+        println!("Unable to read the input from standard input, {}", e);
+        process::exit(1);
+    }
+    let input = aoc::Input::new(input);
+    if matches.opt_present("bench") {
+        aoc::batch::serve_bench(&solution, input, &parse_settings(matches));
+    } else {
+        aoc::batch::serve(&solution, input);
+    }
+    process::exit(0);
+}
+
+/// How many times a benchmark runs each phase, from `--warmup` and `--iterations`.
+fn parse_settings(matches: &getopts::Matches) -> aoc::bench::Settings {
+    let mut settings = aoc::bench::Settings::default();
+    for (name, count) in [
+        ("warmup", &mut settings.warmup),
+        ("iterations", &mut settings.iterations),
+    ] {
+        match matches.opt_get::<usize>(name) {
+            Ok(Some(n)) => *count = n,
+            Ok(None) => {}
+            Err(_) => {
+                println!("Unable to benchmark, --{} expects a number of runs", name);
+                process::exit(1);
+            }
+        }
+    }
+    settings
+}
+
+/// The number of days run at the same time, from `--jobs` or the number of CPUs.
+fn parse_jobs(matches: &getopts::Matches) -> usize {
+    match matches.opt_get::<usize>("jobs") {
+        Ok(Some(jobs)) => jobs,
+        Ok(None) => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        Err(_) => {
+            println!("Unable to run the days, --jobs expects a number of days");
+            process::exit(1);
+        }
+    }
+}
+
+/// Runs every solution of a year on a pool of worker processes, prints the summary and the reason
+/// each failed day failed, and exits. Any failed day exits with a failure once the others have run.
+async fn run_all(year: &i32, context: &aoc::Context, jobs: usize, verbose: bool) -> ! {
+    let solutions = aoc::solution::solutions()
+        .into_iter()
+        .filter(|s| s.year == *year)
+        .collect::<Vec<_>>();
+    if solutions.is_empty() {
+        println!("No solutions registered for {}", year);
+        process::exit(3);
+    }
+
+    let runs = run_days(solutions, context, jobs, verbose).await;
+    println!("AOC {}", year);
+    print!("{}", aoc::batch::render_summary(&runs));
+    report_failures(&runs);
+    process::exit(if aoc::batch::failures(&runs) == 0 {
+        0
+    } else {
+        26
+    });
+}
+
+/// Reruns the solution of every day with a known answer for the profile, limited to a year or a
+/// day when given, prints each answer that no longer matches, and exits. Any mismatch, failed day
+/// or day without a solution exits with a failure, as does finding nothing to verify.
+async fn verify(
+    path: &std::path::Path,
+    year: Option<i32>,
+    day: Option<i32>,
+    context: &aoc::Context,
+    jobs: usize,
+    verbose: bool,
+) -> ! {
+    if !path.is_file() {
+        println!(
+            "No answers file at {}, nothing can be verified",
+            path.display()
+        );
+        process::exit(27);
+    }
+    let known = aoc::known::KnownAnswers::load(path).unwrap_or_else(|e| fail(e));
+    let profile = context.profile.as_deref();
+    let days = known
+        .answers(profile)
+        .filter(|k| year.is_none_or(|y| y == k.year) && day.is_none_or(|d| d == k.day))
+        .map(|k| (k.year, k.day))
+        .collect::<std::collections::BTreeSet<_>>();
+    if days.is_empty() {
+        println!("No known answers to verify in {}", path.display());
+        process::exit(27);
+    }
+
+    let (solutions, missing): (Vec<_>, Vec<_>) = days
+        .iter()
+        .map(|(year, day)| aoc::solution::find(year, day).ok_or((year, day)))
+        .partition(Result::is_ok);
+    let runs = run_days(
+        solutions.into_iter().flatten().collect(),
+        context,
+        jobs,
+        verbose,
+    )
+    .await;
+    report_failures(&runs);
+
+    let verifications = aoc::known::verify(&known, profile, &runs);
+    print!("{}", aoc::known::render_verifications(&verifications));
+    for (year, day) in missing.into_iter().filter_map(Result::err) {
+        println!(
+            "AOC {} Day {:>2}         FAILED    no solution is registered",
+            year, day
+        );
+    }
+    let passed = verifications.iter().filter(|v| v.passed()).count();
+    let total = known_count(&known, profile, &days);
+    println!("{} of {} known answers verified", passed, total);
+    process::exit(if passed == total { 0 } else { 27 });
+}
+
+/// The number of known answers of the profile for the days being verified.
+fn known_count(
+    known: &aoc::known::KnownAnswers,
+    profile: Option<&str>,
+    days: &std::collections::BTreeSet<(i32, i32)>,
+) -> usize {
+    known
+        .answers(profile)
+        .filter(|k| days.contains(&(k.year, k.day)))
+        .count()
+}
+
+/// Records a confirmed answer of a part as a known answer.
+fn record_answer(
+    path: &std::path::Path,
+    year: &i32,
+    day: &i32,
+    part: i32,
+    profile: Option<&str>,
+    answer: &aoc::Answer,
+) {
+    let Some(answer) = answer.submission() else {
+        return;
+    };
+    let mut known = aoc::known::KnownAnswers::load(path).unwrap_or_else(|e| fail(e));
+    let known_answer = aoc::known::KnownAnswer {
+        year: *year,
+        day: *day,
+        part,
+        profile: profile.map(str::to_string),
+        answer,
+    };
+    match known.record(known_answer) {
+        Ok(true) => println!("Recorded the part {} answer in {}", part, path.display()),
+        Ok(false) => {}
+        Err(e) => fail(e),
+    }
+}
+
+/// Runs solutions on a pool of worker processes and shows what each day printed when it failed,
+/// or every day when verbose.
+async fn run_days(
+    solutions: Vec<aoc::solution::Registration>,
+    context: &aoc::Context,
+    jobs: usize,
+    verbose: bool,
+) -> Vec<aoc::batch::DayRun> {
+    let inputs = aoc::batch::read_inputs(solutions, context).await;
+    let runs = aoc::batch::run_parallel(inputs, jobs, |solution| worker_command(solution, &[]));
+
+    for run in &runs {
+        if run.solved().is_none() || verbose {
+            show_output(run);
+        }
+    }
+    runs
+}
+
+/// The command starting this program as the worker of a day, with extra options for the worker.
+fn worker_command(solution: &aoc::solution::Registration, options: &[String]) -> process::Command {
+    let program = std::env::current_exe().unwrap_or_else(|e| {
+        fail(AocError::Io {
+            path: std::path::PathBuf::from("runner"),
+            source: e,
+        })
+    });
+    let mut command = process::Command::new(program);
+    command
+        .arg("--worker")
+        .args(options)
+        .args([solution.year.to_string(), solution.day.to_string()]);
+    command
+}
+
+/// Prints what the solution of a day printed while it ran in a worker.
+fn show_output(run: &aoc::batch::DayRun) {
+    for (stream, output) in [("stdout", &run.stdout), ("stderr", &run.stderr)] {
+        if !output.trim().is_empty() {
+            println!("--- Day {} {}", run.day, stream);
+            println!("{}", output.trim_end());
+        }
+    }
+}
+
+/// Prints the reason each failed day failed.
+fn report_failures(runs: &[aoc::batch::DayRun]) {
+    for run in runs {
+        match &run.status {
+            aoc::batch::Status::Solved(_) => {}
+            aoc::batch::Status::MissingInput(e) => println!("Day {}: {}", run.day, e),
+            aoc::batch::Status::ParseFailed { error, input } => {
+                println!("Day {}: {}", run.day, error.render(input))
+            }
+            aoc::batch::Status::Panicked(message) => {
+                println!("Day {}: Panicked with {:?}", run.day, message)
+            }
+            aoc::batch::Status::Crashed(reason) => println!("Day {}: {}", run.day, reason),
+        }
+    }
+}
+
+/// Shows where the input of a day could not be parsed and exits.
+fn report_parse_error(day: &i32, error: &aoc::ParseError, source: &aoc::Input) -> ! {
+    println!("Error: Unable to parse the input for day {}", day);
+    println!("{}", error.render(source));
+    process::exit(24);
+}
+
+/// Benchmarks a solution, then saves the results as a baseline or compares them with one, and
+/// exits. A significant regression against the baseline exits with a failure.
+fn bench(
+    solution: &aoc::solution::Registration,
+    input: aoc::Input,
+    settings: &aoc::bench::Settings,
+    save: Option<&std::path::Path>,
+    baseline: Option<&std::path::Path>,
+) -> ! {
+    let baseline = baseline.map(|path| aoc::bench::Report::load(path).unwrap_or_else(|e| fail(e)));
+    if let Some(baseline) = &baseline {
+        if (baseline.year, baseline.day) != (solution.year, solution.day) {
+            println!(
+                "Unable to compare, the baseline is for AOC {} Day {}",
+                baseline.year, baseline.day
+            );
+            process::exit(1);
+        }
+    }
+    if cfg!(debug_assertions) {
+        println!("Benchmarking a debug build, build with --release for representative times");
+    }
+
+    // in a worker, so what the solution prints on every run is neither measured nor shown
+    let options = [
+        "--bench".to_string(),
+        format!("--warmup={}", settings.warmup),
+        format!("--iterations={}", settings.iterations),
+    ];
+    let command = worker_command(solution, &options);
+    let report = match aoc::batch::bench_in_worker(solution, input, command) {
+        Ok(report) => report,
+        Err(run) => match &run.status {
+            aoc::batch::Status::ParseFailed { error, input } => {
+                report_parse_error(&solution.day, error, input)
+            }
+            _ => {
+                show_output(&run);
+                report_failures(&[*run]);
+                process::exit(26);
+            }
+        },
+    };
+    println!(
+        "AOC {} Day {} {}, {} runs after {} warmup",
+        report.year, report.day, report.name, report.parse.iterations, report.warmup
+    );
+    print!("{}", aoc::bench::render_report(&report));
+
+    if let Some(path) = save {
+        report.save(path).unwrap_or_else(|e| fail(e));
+        println!("Saved baseline to {}", path.display());
+    }
+
+    let Some(baseline) = baseline else {
+        process::exit(0);
+    };
+    let comparisons = report.compare(&baseline);
+    println!("Compared with the baseline of {}", baseline.name);
+    print!("{}", aoc::bench::render_comparison(&comparisons));
+    let regressed = comparisons
+        .iter()
+        .any(|c| c.change == aoc::bench::Change::Regressed);
+    process::exit(if regressed { 25 } else { 0 });
+}
+
+/// Runs a `cache` subcommand against the cache root and exits.
+///
+/// `list` and `purge` cover every profile unless `--profile` is given, `show` uses the selected
+/// profile or the default one. `encrypt` and `rotate` cover every profile.
+fn cache_command(
+    args: &[String],
+    profile: Option<&str>,
+    root: &std::path::Path,
+    key: Option<aoc::CacheKey>,
+    key_file: Option<&std::path::Path>,
+) -> ! {
+    let usage = || -> ! {
+        println!("Usage: cache list|show YEAR DAY|purge [YEAR [DAY]]|export FILE|import FILE");
+        println!("       cache keygen|encrypt|rotate");
+        process::exit(9);
+    };
+    let require_key = || -> &aoc::CacheKey {
+        key.as_ref().unwrap_or_else(|| {
+            println!(
+                "No cache key found. Run `cache keygen` or set {}.",
+                aoc::crypt::KEY_ENV
+            );
+            process::exit(9);
+        })
+    };
+    let require_key_file = || -> &std::path::Path {
+        key_file.unwrap_or_else(|| {
+            println!("The user config directory is unknown, pass --key-file.");
+            process::exit(9);
+        })
+    };
+    let number = |index: usize| -> Option<i32> {
+        args.get(index)
+            .map(|arg| arg.parse().unwrap_or_else(|_| usage()))
+    };
+    let entries = || aoc::cache::entries(root).unwrap_or_else(|e| fail(e));
+    let filter = profile.map(Some);
+
+    match args.first().map(String::as_str) {
+        Some("list") => {
+            let entries = entries();
+            println!(
+                "{:<12} {:>4} {:>3} {:>8}  fetched",
+                "profile", "year", "day", "bytes"
+            );
+            for entry in entries.iter().filter(|e| e.matches(filter, None, None)) {
+                let fetched = entry
+                    .metadata
+                    .as_ref()
+                    .map(fetched_at)
+                    .unwrap_or_else(|| "unknown".to_string());
+                println!(
+                    "{:<12} {:>4} {:>3} {:>8}  {}{}",
+                    entry.profile.as_deref().unwrap_or("default"),
+                    entry.year,
+                    entry.day,
+                    entry.size,
+                    fetched,
+                    if entry.encrypted { " (encrypted)" } else { "" }
+                );
+            }
+        }
+        Some("show") => {
+            let (Some(year), Some(day)) = (number(1), number(2)) else {
+                usage()
+            };
+            let entries = entries();
+            let Some(entry) = entries
+                .iter()
+                .find(|e| e.matches(Some(profile), Some(year), Some(day)))
+            else {
+                println!("AOC {} Day {} is not cached", year, day);
+                process::exit(9);
+            };
+            println!("Path:    {}", entry.path.display());
+            println!("Profile: {}", entry.profile.as_deref().unwrap_or("default"));
+            println!("Size:    {} bytes", entry.size);
+            println!("Encrypted: {}", if entry.encrypted { "yes" } else { "no" });
+            match &entry.metadata {
+                Some(metadata) => {
+                    println!("Fetched: {}", fetched_at(metadata));
+                    println!("Source:  {}", metadata.url);
+                    if let Some(sha256) = &metadata.sha256 {
+                        println!("SHA-256: {}", sha256);
+                    }
                 }
+                None => println!("Fetched: unknown, cached before metadata was recorded"),
+            }
+            let input = if entry.encrypted {
+                let plain = entry.path.with_file_name(aoc::cache::INPUT_FILE);
+                aoc::crypt::read_encrypted(&plain, key.as_ref())
+            } else {
+                aoc::cache::read_verified(&entry.path)
             };
-            let duration = start_time.elapsed();
-            println!("Elapsed: {:?}", duration);
-            println!("Part 1: {}", part1);
-            println!("Part 2: {}", part2);
+            match input {
+                Ok(Some(input)) => println!("Lines:   {}", input.lines().count()),
+                Ok(None) => {}
+                Err(e) => fail(e),
+            }
+        }
+        Some("purge") => {
+            let (year, day) = (number(1), number(2));
+            let mut removed = 0;
+            for entry in entries().iter().filter(|e| e.matches(filter, year, day)) {
+                match aoc::cache::purge(entry) {
+                    Ok(files) => {
+                        files
+                            .iter()
+                            .for_each(|f| println!("Removed {}", f.display()));
+                        removed += 1;
+                    }
+                    Err(e) => fail(e),
+                }
+            }
+            println!("Purged {} inputs", removed);
+        }
+        Some("export") => {
+            let Some(archive) = args.get(1) else { usage() };
+            match aoc::cache::export(root, std::path::Path::new(archive)) {
+                Ok(files) => println!("Exported {} files to {}", files.len(), archive),
+                Err(e) => fail(e),
+            }
+        }
+        Some("import") => {
+            let Some(archive) = args.get(1) else { usage() };
+            match aoc::cache::import(root, std::path::Path::new(archive)) {
+                Ok(import) => {
+                    for file in &import.skipped {
+                        println!("Kept {}, it is already cached", file.display());
+                    }
+                    println!("Imported {} files from {}", import.added.len(), archive);
+                }
+                Err(e) => fail(e),
+            }
+        }
+        Some("keygen") => {
+            let file = require_key_file();
+            if file.exists() {
+                println!(
+                    "{} already exists, use `cache rotate` to replace it",
+                    file.display()
+                );
+                process::exit(9);
+            }
+            let key = aoc::CacheKey::generate();
+            aoc::crypt::save_key(file, &key).unwrap_or_else(|e| fail(e));
+            println!("Wrote key {} to {}", key.fingerprint(), file.display());
+            println!("Share it with the team outside the repository, then run `cache encrypt`.");
         }
-        Err(e) => println!("Error: {}", e),
+        Some("encrypt") => {
+            let key = require_key();
+            match aoc::crypt::encrypt_cache(root, key) {
+                Ok(files) => {
+                    files
+                        .iter()
+                        .for_each(|f| println!("Encrypted {}", f.display()));
+                    println!(
+                        "Encrypted {} inputs with key {}",
+                        files.len(),
+                        key.fingerprint()
+                    );
+                }
+                Err(e) => fail(e),
+            }
+        }
+        Some("rotate") => {
+            let old = require_key();
+            let file = require_key_file();
+            // the new key is saved first so inputs already rewritten stay readable if the
+            // rotation is interrupted
+            let mut pending = file.as_os_str().to_os_string();
+            pending.push(".new");
+            let pending = std::path::PathBuf::from(pending);
+            let new = aoc::CacheKey::generate();
+            aoc::crypt::save_key(&pending, &new).unwrap_or_else(|e| fail(e));
+
+            match aoc::crypt::rotate_cache(root, old, &new) {
+                Ok(files) => {
+                    aoc::crypt::save_key(file, &new).unwrap_or_else(|e| fail(e));
+                    let _ = std::fs::remove_file(&pending);
+                    println!("Re-encrypted {} inputs", files.len());
+                    println!("Wrote key {} to {}", new.fingerprint(), file.display());
+                    if std::env::var_os(aoc::crypt::KEY_ENV).is_some() {
+                        println!(
+                            "Update {} to the new key, it takes precedence over the file.",
+                            aoc::crypt::KEY_ENV
+                        );
+                    }
+                }
+                Err(e) => {
+                    println!("The new key is kept in {}", pending.display());
+                    fail(e)
+                }
+            }
+        }
+        _ => usage(),
+    }
+    process::exit(0);
+}
+
+/// Adds a solution crate to the dependencies of the runner so it is linked and registers itself.
+///
+/// # Returns
+///
+/// * `Ok(true)` - If the dependency was added.
+/// * `Ok(false)` - If the runner already depends on the crate.
+fn link_solution(manifest: &std::path::Path, name: &str) -> std::io::Result<bool> {
+    let text = std::fs::read_to_string(manifest)?;
+    let mut lines = text.lines().map(str::to_string).collect::<Vec<_>>();
+    if lines
+        .iter()
+        .any(|l| l.split('=').next().map(str::trim) == Some(name))
+    {
+        return Ok(false);
+    }
+    // next to the other path dependencies, which are all in the dependencies table
+    let Some(last) = lines.iter().rposition(|l| l.contains("path = \"../")) else {
+        return Err(std::io::Error::other("the runner has no path dependencies"));
+    };
+    lines.insert(last + 1, format!("{0} = {{ path = \"../{0}\" }}", name));
+    std::fs::write(manifest, lines.join("\n") + "\n")?;
+    Ok(true)
+}
+
+/// Keeps the description next to the day crate so it can be read offline and is committed with
+/// the solution.
+fn save_description(crate_dir: &std::path::Path, description: &str) {
+    match aoc::puzzle::save_description(crate_dir, description) {
+        Ok(Some(file)) => println!("Wrote {}", file.display()),
+        Ok(None) => {}
+        Err(e) => fail(e),
+    }
+}
+
+fn fetched_at(metadata: &aoc::cache::Metadata) -> String {
+    let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(metadata.fetched_at);
+    aoc::unlock::format_timestamp(&time)
+}
+
+/// Parses a list of years such as `2025`, `2023,2024` or `2015-2025`.
+fn parse_years(text: &str) -> Option<Vec<i32>> {
+    let mut years = Vec::new();
+    for part in text.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let first = first.trim().parse::<i32>().ok()?;
+                let last = last.trim().parse::<i32>().ok()?;
+                years.extend(first..=last);
+            }
+            None => years.push(part.trim().parse().ok()?),
+        }
+    }
+    Some(years)
+}
+
+/// Lists every unlocked day of the year whose input is not cached.
+fn report_missing(year: &i32, context: &aoc::Context) {
+    let clock = aoc::unlock::SystemClock;
+    if let Ok(missing) = aoc::prefetch::missing_inputs(&[*year], context, &clock) {
+        let days = missing
+            .iter()
+            .map(|(_, day)| day.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("Inputs missing from the cache for AOC {}: {}", year, days);
+        println!(
+            "Run with --prefetch {} while online to download them.",
+            year
+        );
     }
 }